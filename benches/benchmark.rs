@@ -2,7 +2,9 @@
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId};
 use document_similarity_analyzer::core::{
-    normalize_text, tokenize, compute_tf, analyze_documents
+    normalize_text, tokenize, compute_tf, analyze_sentence_similarity_with_options,
+    compute_cosine_similarity, compute_similarity_matrix, compute_similarity_matrix_as, cosine_similarity,
+    dot_product, f16, l2_normalize, SentenceAnalysisOptions, SentenceDocument, SparseVector, Vocabulary,
 };
 
 fn bench_normalize_text(c: &mut Criterion) {
@@ -29,19 +31,96 @@ fn bench_compute_tf(c: &mut Criterion) {
     });
 }
 
+fn bench_cosine(c: &mut Criterion) {
+    let a: Vec<String> = (0..30).map(|i| format!("word{}", i % 25)).collect();
+    let b: Vec<String> = (0..30).map(|i| format!("word{}", (i * 3) % 40)).collect();
+    let mut group = c.benchmark_group("cosine");
+
+    let (map_a, map_b) = (compute_tf(&a), compute_tf(&b));
+    group.bench_function("hashmap", |bench| {
+        bench.iter(|| compute_cosine_similarity(black_box(&map_a), black_box(&map_b)))
+    });
+
+    let mut vocabulary = Vocabulary::new();
+    let sparse_a = SparseVector::term_frequencies(&vocabulary.intern_all(&a));
+    let sparse_b = SparseVector::term_frequencies(&vocabulary.intern_all(&b));
+    group.bench_function("sparse", |bench| {
+        bench.iter(|| black_box(&sparse_a).cosine(black_box(&sparse_b)))
+    });
+
+    let (unit_a, unit_b) = (sparse_a.normalized(), sparse_b.normalized());
+    group.bench_function("sparse_normalized_dot", |bench| {
+        bench.iter(|| black_box(&unit_a).dot(black_box(&unit_b)))
+    });
+
+    let dense_a: Vec<f32> = (0..512).map(|i| (i as f32 * 0.7).sin()).collect();
+    let dense_b: Vec<f32> = (0..512).map(|i| (i as f32 * 1.3).cos()).collect();
+    group.bench_function("dense", |bench| {
+        bench.iter(|| cosine_similarity(black_box(&dense_a), black_box(&dense_b)))
+    });
+
+    let (mut unit_dense_a, mut unit_dense_b) = (dense_a.clone(), dense_b.clone());
+    l2_normalize(&mut unit_dense_a);
+    l2_normalize(&mut unit_dense_b);
+    group.bench_function("dense_normalized_dot", |bench| {
+        bench.iter(|| dot_product(black_box(&unit_dense_a), black_box(&unit_dense_b)))
+    });
+
+    group.finish();
+}
+
+fn bench_similarity_matrix(c: &mut Criterion) {
+    let mut group = c.benchmark_group("similarity_matrix");
+    group.sample_size(10);
+
+    for count in [100, 1000] {
+        let vectors: Vec<Vec<f32>> = (0..count)
+            .map(|row| (0..256).map(|i| ((row * 256 + i) as f32 * 0.37).sin().max(0.0)).collect())
+            .collect();
+
+        group.bench_with_input(BenchmarkId::new("f32", count), &vectors, |b, vectors| {
+            b.iter(|| compute_similarity_matrix(black_box(vectors)))
+        });
+        group.bench_with_input(BenchmarkId::new("f16", count), &vectors, |b, vectors| {
+            b.iter(|| compute_similarity_matrix_as::<f16>(black_box(vectors)))
+        });
+    }
+
+    group.finish();
+}
+
+/// Sentences drawn from a shared word pool so that documents overlap
+fn sample_documents(count: usize, sentences: usize) -> Vec<SentenceDocument> {
+    let words = [
+        "glacier", "valley", "river", "floodplain", "desert", "rain", "forest", "canopy",
+        "ocean", "current", "mountain", "erosion", "climate", "season", "delta", "sediment",
+        "volcano", "basin", "tundra", "monsoon", "estuary", "plateau", "canyon", "reef",
+    ];
+
+    (0..count)
+        .map(|doc| {
+            let sentences = (0..sentences)
+                .map(|s| {
+                    (0..12)
+                        .map(|w| words[(doc * 7 + s * 5 + w * 3) % words.len()])
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .collect();
+            SentenceDocument::new(format!("doc{}.txt", doc), sentences)
+        })
+        .collect()
+}
+
 fn bench_full_pipeline(c: &mut Criterion) {
     let mut group = c.benchmark_group("full_pipeline");
+    let options = SentenceAnalysisOptions::default();
     
     for size in [5, 10, 25, 50].iter() {
-        let docs: Vec<String> = (0..*size)
-            .map(|i| format!(
-                "This is document number {} with some sample text for testing the similarity analysis pipeline",
-                i
-            ))
-            .collect();
+        let docs = sample_documents(*size, 40);
         
         group.bench_with_input(BenchmarkId::new("docs", size), &docs, |b, docs| {
-            b.iter(|| analyze_documents(black_box(docs)))
+            b.iter(|| analyze_sentence_similarity_with_options(black_box(docs), &options))
         });
     }
     
@@ -53,6 +132,8 @@ criterion_group!(
     bench_normalize_text,
     bench_tokenize,
     bench_compute_tf,
+    bench_cosine,
+    bench_similarity_matrix,
     bench_full_pipeline
 );
 criterion_main!(benches);
@@ -1,33 +1,91 @@
 //! File upload handler for sentence-level analysis
 
-use axum::extract::Multipart;
-use axum::http::StatusCode;
-use axum::response::{IntoResponse, Response};
+use axum::body::Body;
+use axum::extract::{Multipart, Query};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{Html, IntoResponse, Response};
 use axum::Json;
+use serde::Deserialize;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
 use std::time::Instant;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{mpsc, oneshot};
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
+use tracing::warn;
 
-use crate::extraction::{extract_text, FileType};
-use crate::sentence::split_sentences;
-use crate::core::{analyze_sentence_similarity, SentenceDocument};
-use crate::models::{SentenceAnalysisResponse, AnalysisMetadata};
+use crate::extraction::{
+    detect_file_type, expand_archive, extract_text, ArchiveError, ArchiveLimits, FileType,
+};
+use crate::sentence::{split_sentences_with_language, Language};
+use crate::core::{
+    analyze_sentence_similarity_streaming_within_budget, analyze_sentence_similarity_within_budget,
+    AlignmentMode, MemoryBudgetExceeded, QuoteMode, SentenceAnalysisOptions, SentenceDocument,
+};
+use crate::models::{AggregationStrategy, AnalysisStreamRecord, ClusterMethod, MatchCursor};
+use crate::report::{render_html_report, write_report, ReportFormat};
 
 /// Constants for file upload limits
 const MAX_FILE_SIZE: usize = 10 * 1024 * 1024; // 10 MB
 const MAX_TOTAL_SIZE: usize = 50 * 1024 * 1024; // 50 MB
+/// Request body limit: all files plus room for the form fields
+pub(crate) const MAX_REQUEST_SIZE: usize = MAX_TOTAL_SIZE + 1024 * 1024;
 const MAX_FILES: usize = 5;
 const MIN_FILES: usize = 2;
+const MAX_ARCHIVE_ENTRIES: usize = 1000;
+const MAX_ARCHIVE_DEPTH: usize = 3;
 const DEFAULT_THRESHOLD: f32 = 0.70;
+const DEFAULT_MIN_TOKENS: usize = 3;
+const DEFAULT_MIN_CHARS: usize = 0;
+const DEFAULT_CLUSTER_THRESHOLD: f32 = 0.50;
+/// Records buffered between the analysis and a slow streaming client
+const STREAM_BUFFER_RECORDS: usize = 1024;
+/// Peak-memory budget of one analysis, overridden by `$ANALYSIS_MEMORY_BUDGET_MB`
+const DEFAULT_MEMORY_BUDGET_MB: usize = 1024;
 
 /// Handler for POST /api/analyze with multipart file upload
 ///
-/// Accepts up to 5 files (PDF/DOCX/TXT) and returns sentence-level similarity analysis.
+/// Accepts up to 5 files (PDF/DOCX/ODT/RTF/PPTX/ODP/TXT/HTML/Markdown) and returns sentence-level similarity analysis.
+/// ZIP archives are expanded and each supported entry counts as one file.
+/// Uploads are spooled to temporary files and extracted one at a time; an
+/// analysis whose estimated memory, or whose kept matches, exceed the budget
+/// is rejected.
+/// The output format is chosen by `?format=` (json, html, csv, global_csv,
+/// matrix_csv, ndjson) or, when absent, by the `Accept` header.
+/// `?stream=true` streams matches as NDJSON records while they are found.
 pub async fn analyze_files_handler(
+    Query(query): Query<AnalyzeQuery>,
+    headers: HeaderMap,
     mut multipart: Multipart,
-) -> Result<Json<SentenceAnalysisResponse>, FileUploadError> {
+) -> Result<Response, FileUploadError> {
     let start_time = Instant::now();
-    
-    // Collect files and threshold from multipart form
-    let (files, threshold) = extract_files_and_threshold(&mut multipart).await?;
+
+    let format = match query.format {
+        Some(name) => ReportFormat::from_name(&name)
+            .ok_or(FileUploadError::InvalidOption("format".to_string(), name))?,
+        None => headers
+            .get(header::ACCEPT)
+            .and_then(|accept| accept.to_str().ok())
+            .and_then(ReportFormat::from_accept)
+            .unwrap_or_default(),
+    };
+    let stream = query.stream.unwrap_or(false);
+    if stream && !matches!(format, ReportFormat::Json | ReportFormat::Ndjson) {
+        return Err(FileUploadError::InvalidOption("stream".to_string(), "true".to_string()));
+    }
+
+    // Collect files and options from multipart form
+    let UploadForm { files, template, mut options, language } =
+        extract_upload_form(&mut multipart).await?;
+
+    // Detect file types and expand ZIP archives into their documents
+    let (files, mut warnings) = expand_archives(files)?;
+
+    // Split the assignment template into the sentences to suppress
+    if let Some(template) = template {
+        options.template = template_sentences(template, language, &mut warnings)?;
+    }
 
     // Validate minimum files
     if files.len() < MIN_FILES {
@@ -37,17 +95,17 @@ pub async fn analyze_files_handler(
     // Extract text from files
     let documents: Result<Vec<SentenceDocument>, FileUploadError> = files
         .into_iter()
-        .map(|(filename, data)| {
-            // Detect file type
-            let file_type = FileType::from_filename(&filename)
-                .ok_or_else(|| FileUploadError::UnsupportedFileType(filename.clone()))?;
+        .map(|(file, file_type)| {
+            let data = file.read()?;
+            let filename = file.filename;
 
             // Extract text
             let text = extract_text(&data, file_type)
                 .map_err(|e| FileUploadError::ExtractionError(filename.clone(), e))?;
+            drop(data);
 
             // Split into sentences
-            let sentences = split_sentences(&text);
+            let sentences = split_sentences_with_language(&text, language);
 
             if sentences.is_empty() {
                 return Err(FileUploadError::EmptyDocument(filename));
@@ -59,27 +117,136 @@ pub async fn analyze_files_handler(
 
     let documents = documents?;
 
-    // Count total sentences
-    let total_sentences: usize = documents.iter().map(|d| d.sentences.len()).sum();
+    let budget = memory_budget();
+    if stream {
+        return stream_analysis(documents, options, warnings, start_time, budget).await;
+    }
 
-    // Analyze similarity
-    let (matches, global_similarity) = analyze_sentence_similarity(&documents, threshold);
+    // Analyze similarity, stopping once the kept matches exceed the memory budget
+    let analysis = analyze_sentence_similarity_within_budget(&documents, &options, budget)
+        .map_err(FileUploadError::MemoryBudgetExceeded)?;
 
     // Compute processing time
     let processing_time_ms = start_time.elapsed().as_millis() as u64;
 
-    // Build metadata
-    let metadata = AnalysisMetadata::new(
-        documents.len(),
-        total_sentences,
-        processing_time_ms,
-        threshold,
-    );
-
     // Build response
-    let response = SentenceAnalysisResponse::new(metadata, matches, global_similarity);
+    let response = analysis.into_response(&documents, options.threshold, processing_time_ms, warnings);
+
+    Ok(match format {
+        ReportFormat::Json => Json(response).into_response(),
+        ReportFormat::Html => Html(render_html_report(&response, &documents)).into_response(),
+        export => {
+            let mut body = Vec::new();
+            write_report(export, &response, &documents, &mut body)
+                .map_err(|e| FileUploadError::ExportError(e.to_string()))?;
+            let disposition = format!("attachment; filename=\"{}\"", export.file_name());
+            (
+                [
+                    (header::CONTENT_TYPE, export.content_type().to_string()),
+                    (header::CONTENT_DISPOSITION, disposition),
+                ],
+                body,
+            )
+                .into_response()
+        }
+    })
+}
+
+/// Query parameters of POST /api/analyze
+#[derive(Debug, Default, Deserialize)]
+pub struct AnalyzeQuery {
+    /// Output format: `json` (default), `html`, `csv`, `global_csv`, `matrix_csv` or `ndjson`
+    pub format: Option<String>,
+    /// Stream matches as NDJSON records followed by a summary record
+    pub stream: Option<bool>,
+}
+
+/// Run the analysis on a blocking thread and stream its records as NDJSON
+///
+/// Matches are sent in batches as they are found; a bounded channel keeps
+/// memory flat when the client reads slowly. The last record is the summary
+/// with metadata and global similarity. An analysis over the memory budget is
+/// rejected before the response starts.
+async fn stream_analysis(
+    documents: Vec<SentenceDocument>,
+    options: SentenceAnalysisOptions,
+    warnings: Vec<String>,
+    start_time: Instant,
+    budget: usize,
+) -> Result<Response, FileUploadError> {
+    let (sender, mut receiver) = mpsc::channel::<io::Result<Vec<u8>>>(STREAM_BUFFER_RECORDS);
+    let (budget_sender, budget_receiver) = oneshot::channel::<MemoryBudgetExceeded>();
+
+    let error_sender = sender.clone();
+    let analysis = tokio::task::spawn_blocking(move || {
+        // A closed channel means the client went away; stop comparing pairs
+        let analysis = analyze_sentence_similarity_streaming_within_budget(
+            &documents,
+            &options,
+            budget,
+            |m| {
+                let _ = sender.blocking_send(ndjson_record(&AnalysisStreamRecord::Match(m)));
+            },
+            || sender.is_closed(),
+        );
+        let analysis = match analysis {
+            Ok(analysis) => analysis,
+            Err(error) => {
+                let _ = budget_sender.send(error);
+                return;
+            }
+        };
+        if sender.is_closed() {
+            return;
+        }
+
+        let processing_time_ms = start_time.elapsed().as_millis() as u64;
+        let response = analysis.into_response(&documents, options.threshold, processing_time_ms, warnings);
+        let _ = sender.blocking_send(ndjson_record(&AnalysisStreamRecord::Summary(Box::new(response))));
+    });
+
+    // End a stream whose analysis panicked with an error record, so it
+    // cannot be mistaken for a complete result
+    tokio::spawn(async move {
+        if let Err(error) = analysis.await {
+            warn!("Streamed analysis failed: {}", error);
+            let record = AnalysisStreamRecord::Error {
+                message: "analysis failed before completing".to_string(),
+            };
+            let _ = error_sender.send(ndjson_record(&record)).await;
+        }
+    });
+
+    // The budget is checked before any record is sent, so an analysis that
+    // ends without records may still be rejected with a status code
+    let Some(first) = receiver.recv().await else {
+        return match budget_receiver.await {
+            Ok(error) => Err(FileUploadError::MemoryBudgetExceeded(error)),
+            Err(_) => Ok(([(header::CONTENT_TYPE, ReportFormat::Ndjson.content_type())], Body::empty()).into_response()),
+        };
+    };
+
+    let records = tokio_stream::once(first).chain(ReceiverStream::new(receiver));
+    Ok((
+        [(header::CONTENT_TYPE, ReportFormat::Ndjson.content_type())],
+        Body::from_stream(records),
+    )
+        .into_response())
+}
+
+fn ndjson_record(record: &AnalysisStreamRecord) -> io::Result<Vec<u8>> {
+    let mut line = serde_json::to_vec(record)?;
+    line.push(b'\n');
+    Ok(line)
+}
 
-    Ok(Json(response))
+/// Memory budget of an analysis in bytes, from `$ANALYSIS_MEMORY_BUDGET_MB`
+fn memory_budget() -> usize {
+    let megabytes = std::env::var("ANALYSIS_MEMORY_BUDGET_MB")
+        .ok()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .unwrap_or(DEFAULT_MEMORY_BUDGET_MB);
+    megabytes.saturating_mul(1024 * 1024)
 }
 
 /// Health check endpoint
@@ -87,6 +254,50 @@ pub async fn health_handler() -> &'static str {
     "OK"
 }
 
+/// A file received from the multipart form
+struct UploadedFile {
+    filename: String,
+    /// Content-Type declared by the client, if any
+    content_type: Option<String>,
+    /// Contents, spooled to an anonymous temporary file
+    spool: File,
+    size: usize,
+}
+
+impl UploadedFile {
+    /// Spool in-memory contents (an archive entry) to a temporary file
+    fn from_bytes(filename: String, content_type: Option<String>, data: &[u8]) -> Result<Self, FileUploadError> {
+        let mut spool = tempfile::tempfile().map_err(|e| FileUploadError::SpoolError(e.to_string()))?;
+        spool.write_all(data).map_err(|e| FileUploadError::SpoolError(e.to_string()))?;
+
+        Ok(Self { filename, content_type, spool, size: data.len() })
+    }
+
+    /// Read the spooled contents back into memory
+    fn read(&self) -> Result<Vec<u8>, FileUploadError> {
+        let mut data = Vec::with_capacity(self.size);
+        let mut spool = &self.spool;
+        spool
+            .seek(SeekFrom::Start(0))
+            .and_then(|_| spool.read_to_end(&mut data))
+            .map_err(|e| FileUploadError::SpoolError(e.to_string()))?;
+        Ok(data)
+    }
+}
+
+/// Files and options received from the multipart form
+struct UploadForm {
+    files: Vec<UploadedFile>,
+    /// Assignment template whose sentences are removed from every submission
+    template: Option<UploadedFile>,
+    options: SentenceAnalysisOptions,
+    /// Language hint for sentence splitting
+    language: Language,
+}
+
+/// An uploaded (or archive-expanded) file with its detected type
+type DetectedFile = (UploadedFile, FileType);
+
 /// Errors that can occur during file upload and processing
 #[derive(Debug)]
 pub enum FileUploadError {
@@ -100,8 +311,14 @@ pub enum FileUploadError {
     UnsupportedFileType(String),
     ExtractionError(String, String),
     EmptyDocument(String),
+    ArchiveError(String, ArchiveError),
     InvalidThreshold(String),
     InvalidThresholdRange(f32),
+    InvalidLanguage(String),
+    InvalidOption(String, String),
+    ExportError(String),
+    SpoolError(String),
+    MemoryBudgetExceeded(MemoryBudgetExceeded),
 }
 
 impl IntoResponse for FileUploadError {
@@ -143,7 +360,7 @@ impl IntoResponse for FileUploadError {
             FileUploadError::UnsupportedFileType(filename) => {
                 (
                     StatusCode::BAD_REQUEST,
-                    format!("Unsupported file type: {}. Allowed: PDF, DOCX, TXT", filename),
+                    format!("Unsupported file type: {}. Allowed: PDF, DOCX, ODT, RTF, PPTX, ODP, TXT, HTML, Markdown, ZIP", filename),
                 )
             }
             FileUploadError::ExtractionError(filename, error) => {
@@ -158,6 +375,14 @@ impl IntoResponse for FileUploadError {
                     format!("Document '{}' contains no text or sentences", filename),
                 )
             }
+            FileUploadError::ArchiveError(filename, error) => {
+                let status = match error {
+                    ArchiveError::TooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+                    ArchiveError::Invalid(_) => StatusCode::UNPROCESSABLE_ENTITY,
+                    ArchiveError::TooManyEntries(_) | ArchiveError::TooDeep(_) => StatusCode::BAD_REQUEST,
+                };
+                (status, format!("Failed to expand archive '{}': {}", filename, error))
+            }
             FileUploadError::InvalidThreshold(value) => {
                 (
                     StatusCode::BAD_REQUEST,
@@ -170,21 +395,64 @@ impl IntoResponse for FileUploadError {
                     format!("Threshold {} out of range. Must be between 0.0 and 1.0", value),
                 )
             }
+            FileUploadError::InvalidLanguage(value) => {
+                (
+                    StatusCode::BAD_REQUEST,
+                    format!(
+                        "Invalid language: '{}'. Supported: auto, en, id, zh, ja, ko, ar, hi",
+                        value
+                    ),
+                )
+            }
+            FileUploadError::InvalidOption(name, value) => {
+                (
+                    StatusCode::BAD_REQUEST,
+                    format!("Invalid value for '{}': '{}'", name, value),
+                )
+            }
+            FileUploadError::ExportError(e) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to render report: {}", e))
+            }
+            FileUploadError::SpoolError(e) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to buffer upload: {}", e))
+            }
+            FileUploadError::MemoryBudgetExceeded(e) => {
+                (
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    format!("Documents too large to analyze: {}. Upload fewer or shorter documents", e),
+                )
+            }
         };
 
         (status, message).into_response()
     }
 }
 
-/// Extract files and threshold from multipart form data
-async fn extract_files_and_threshold(
+/// Extract files, threshold and language from multipart form data
+async fn extract_upload_form(
     multipart: &mut Multipart,
-) -> Result<(Vec<(String, Vec<u8>)>, f32), FileUploadError> {
-    let mut files: Vec<(String, Vec<u8>)> = Vec::new();
+) -> Result<UploadForm, FileUploadError> {
+    let mut files: Vec<UploadedFile> = Vec::new();
     let mut threshold_value: Option<f32> = None;
+    let mut language = Language::Auto;
+    let mut min_tokens = DEFAULT_MIN_TOKENS;
+    let mut min_chars = DEFAULT_MIN_CHARS;
+    let mut quotes = QuoteMode::Off;
+    let mut include_references = false;
+    let mut similarity_matrices = false;
+    let mut clustering: Option<ClusterMethod> = None;
+    let mut cluster_threshold = DEFAULT_CLUSTER_THRESHOLD;
+    let mut template: Option<UploadedFile> = None;
+    let mut boilerplate_fraction: Option<f32> = None;
+    let mut aggregation = AggregationStrategy::Mean;
+    let mut alignment = AlignmentMode::Off;
+    let mut limit: Option<usize> = None;
+    let mut offset = 0usize;
+    let mut cursor: Option<MatchCursor> = None;
+    let mut per_document_pair_limit: Option<usize> = None;
     let mut total_size = 0usize;
 
-    while let Some(field) = multipart.next_field().await
+    while let Some(mut field) = multipart.next_field().await
         .map_err(|e| FileUploadError::InvalidMultipart(e.to_string()))? {
         
         let field_name = field.name().unwrap_or("").to_string();
@@ -200,29 +468,187 @@ async fn extract_files_and_threshold(
             );
             continue;
         }
+
+        // Check if this is the language field
+        if field_name == "language" {
+            let code = field.text().await
+                .map_err(|e| FileUploadError::ReadError(e.to_string()))?;
+
+            language = Language::from_code(&code)
+                .ok_or(FileUploadError::InvalidLanguage(code))?;
+            continue;
+        }
+
+        // Check if this is a minimum sentence length field
+        if field_name == "min_tokens" || field_name == "min_chars" {
+            let value = field.text().await
+                .map_err(|e| FileUploadError::ReadError(e.to_string()))?;
+
+            let parsed = value.trim().parse::<usize>()
+                .map_err(|_| FileUploadError::InvalidOption(field_name.clone(), value.clone()))?;
+            if field_name == "min_tokens" {
+                min_tokens = parsed;
+            } else {
+                min_chars = parsed;
+            }
+            continue;
+        }
+
+        // Check if this is the quotation handling field
+        if field_name == "quotes" {
+            let value = field.text().await
+                .map_err(|e| FileUploadError::ReadError(e.to_string()))?;
+
+            quotes = QuoteMode::from_name(&value)
+                .ok_or(FileUploadError::InvalidOption(field_name, value))?;
+            continue;
+        }
+
+        // Check if this is the boilerplate suppression field
+        if field_name == "boilerplate_fraction" {
+            let value = field.text().await
+                .map_err(|e| FileUploadError::ReadError(e.to_string()))?;
+
+            let fraction = value.trim().parse::<f32>()
+                .ok()
+                .filter(|fraction| (0.0..1.0).contains(fraction))
+                .ok_or(FileUploadError::InvalidOption(field_name, value))?;
+            boilerplate_fraction = Some(fraction);
+            continue;
+        }
+
+        // Check if this is the aggregation strategy field
+        if field_name == "aggregation" {
+            let value = field.text().await
+                .map_err(|e| FileUploadError::ReadError(e.to_string()))?;
+
+            aggregation = AggregationStrategy::from_name(&value)
+                .ok_or(FileUploadError::InvalidOption(field_name, value))?;
+            continue;
+        }
+
+        // Check if this is the document clustering method field
+        if field_name == "clustering" {
+            let value = field.text().await
+                .map_err(|e| FileUploadError::ReadError(e.to_string()))?;
+
+            clustering = Some(ClusterMethod::from_name(&value)
+                .ok_or(FileUploadError::InvalidOption(field_name, value))?);
+            continue;
+        }
+
+        // Check if this is the clustering threshold field
+        if field_name == "cluster_threshold" {
+            let value = field.text().await
+                .map_err(|e| FileUploadError::ReadError(e.to_string()))?;
+
+            cluster_threshold = value.trim().parse::<f32>()
+                .ok()
+                .filter(|threshold| (0.0..=1.0).contains(threshold))
+                .ok_or(FileUploadError::InvalidOption(field_name, value))?;
+            continue;
+        }
+
+        // Check if this is the sentence alignment field
+        if field_name == "alignment" {
+            let value = field.text().await
+                .map_err(|e| FileUploadError::ReadError(e.to_string()))?;
+
+            alignment = AlignmentMode::from_name(&value)
+                .ok_or(FileUploadError::InvalidOption(field_name, value))?;
+            continue;
+        }
+
+        // Check if this is the reference section field
+        if field_name == "include_references" {
+            let value = field.text().await
+                .map_err(|e| FileUploadError::ReadError(e.to_string()))?;
+
+            include_references = value.trim().parse::<bool>()
+                .map_err(|_| FileUploadError::InvalidOption(field_name, value))?;
+            continue;
+        }
+
+        // Check if this is the similarity matrix field
+        if field_name == "similarity_matrix" {
+            let value = field.text().await
+                .map_err(|e| FileUploadError::ReadError(e.to_string()))?;
+
+            similarity_matrices = value.trim().parse::<bool>()
+                .map_err(|_| FileUploadError::InvalidOption(field_name, value))?;
+            continue;
+        }
+
+        // Check if this is a match paging field (`max_matches` is an alias of `limit`)
+        if matches!(
+            field_name.as_str(),
+            "limit" | "max_matches" | "offset" | "per_document_pair_limit"
+        ) {
+            let value = field.text().await
+                .map_err(|e| FileUploadError::ReadError(e.to_string()))?;
+
+            let parsed = value.trim().parse::<usize>()
+                .map_err(|_| FileUploadError::InvalidOption(field_name.clone(), value.clone()))?;
+            match field_name.as_str() {
+                "offset" => offset = parsed,
+                "per_document_pair_limit" => per_document_pair_limit = Some(parsed),
+                _ => limit = Some(parsed),
+            }
+            continue;
+        }
+
+        // Check if this is the cursor of a previous page
+        if field_name == "cursor" {
+            let value = field.text().await
+                .map_err(|e| FileUploadError::ReadError(e.to_string()))?;
+
+            cursor = Some(MatchCursor::decode(&value)
+                .ok_or(FileUploadError::InvalidOption(field_name, value))?);
+            continue;
+        }
         
         // Otherwise, it's a file field
         let filename = field.file_name()
             .ok_or(FileUploadError::MissingFilename)?
             .to_string();
+        let content_type = field.content_type().map(|mime| mime.to_string());
 
-        let data = field.bytes().await
-            .map_err(|e| FileUploadError::ReadError(e.to_string()))?
-            .to_vec();
+        // Stream the file to a temporary file, checking sizes chunk by chunk
+        let spool = tempfile::tempfile().map_err(|e| FileUploadError::SpoolError(e.to_string()))?;
+        let mut spool = tokio::fs::File::from_std(spool);
+        let mut size = 0usize;
+        while let Some(chunk) = field.chunk().await
+            .map_err(|e| FileUploadError::ReadError(e.to_string()))? {
 
-        // Check individual file size
-        if data.len() > MAX_FILE_SIZE {
-            return Err(FileUploadError::FileTooLarge(filename, MAX_FILE_SIZE));
+            size += chunk.len();
+
+            // Check individual file size
+            if size > MAX_FILE_SIZE {
+                return Err(FileUploadError::FileTooLarge(filename, MAX_FILE_SIZE));
+            }
+
+            // Check total size
+            if total_size + size > MAX_TOTAL_SIZE {
+                return Err(FileUploadError::TotalSizeTooLarge(MAX_TOTAL_SIZE));
+            }
+
+            spool.write_all(&chunk).await
+                .map_err(|e| FileUploadError::SpoolError(e.to_string()))?;
         }
+        spool.flush().await
+            .map_err(|e| FileUploadError::SpoolError(e.to_string()))?;
+        let spool = spool.into_std().await;
 
-        total_size += data.len();
+        total_size += size;
+        let file = UploadedFile { filename, content_type, spool, size };
 
-        // Check total size
-        if total_size > MAX_TOTAL_SIZE {
-            return Err(FileUploadError::TotalSizeTooLarge(MAX_TOTAL_SIZE));
+        // The template is not a submission and does not count as a file
+        if field_name == "template" {
+            template = Some(file);
+            continue;
         }
 
-        files.push((filename, data));
+        files.push(file);
 
         // Check max files
         if files.len() > MAX_FILES {
@@ -234,9 +660,133 @@ async fn extract_files_and_threshold(
     let threshold = threshold_value.unwrap_or(DEFAULT_THRESHOLD);
     
     // Validate threshold range
-    if threshold < 0.0 || threshold > 1.0 {
+    if !(0.0..=1.0).contains(&threshold) {
         return Err(FileUploadError::InvalidThresholdRange(threshold));
     }
 
-    Ok((files, threshold))
+    let options = SentenceAnalysisOptions {
+        threshold,
+        min_tokens,
+        min_chars,
+        quotes,
+        include_references,
+        boilerplate_fraction,
+        aggregation,
+        alignment,
+        limit,
+        offset,
+        cursor,
+        per_document_pair_limit,
+        similarity_matrices,
+        clustering,
+        cluster_threshold,
+        ..SentenceAnalysisOptions::default()
+    };
+
+    Ok(UploadForm { files, template, options, language })
+}
+
+/// Extract and split the uploaded assignment template
+fn template_sentences(
+    template: UploadedFile,
+    language: Language,
+    warnings: &mut Vec<String>,
+) -> Result<Vec<String>, FileUploadError> {
+    let data = template.read()?;
+    let file_type = detect_upload_type(&template, &data, warnings)?;
+    if file_type == FileType::Zip {
+        return Err(FileUploadError::UnsupportedFileType(template.filename));
+    }
+
+    let text = extract_text(&data, file_type)
+        .map_err(|e| FileUploadError::ExtractionError(template.filename.clone(), e))?;
+
+    Ok(split_sentences_with_language(&text, language))
+}
+
+/// Detect file types and expand ZIP archives into their documents
+///
+/// Each supported archive entry becomes its own file, labeled by its path
+/// inside the archive. Limits on file count and total size are enforced
+/// after expansion. Returns the files with their detected types plus
+/// warnings (type mismatches, skipped archive entries).
+fn expand_archives(
+    files: Vec<UploadedFile>,
+) -> Result<(Vec<DetectedFile>, Vec<String>), FileUploadError> {
+    let limits = ArchiveLimits {
+        max_entries: MAX_ARCHIVE_ENTRIES,
+        max_total_size: MAX_TOTAL_SIZE,
+        max_depth: MAX_ARCHIVE_DEPTH,
+    };
+    let mut expanded_files: Vec<DetectedFile> = Vec::new();
+    let mut warnings: Vec<String> = Vec::new();
+
+    for file in files {
+        let data = file.read()?;
+        let file_type = detect_upload_type(&file, &data, &mut warnings)?;
+        if file_type != FileType::Zip {
+            expanded_files.push((file, file_type));
+            continue;
+        }
+
+        let archive = expand_archive(&data, &limits)
+            .map_err(|e| FileUploadError::ArchiveError(file.filename.clone(), e))?;
+
+        if !archive.skipped.is_empty() {
+            warnings.push(format!(
+                "'{}': skipped {} unsupported entries: {}",
+                file.filename,
+                archive.skipped.len(),
+                archive.skipped.join(", ")
+            ));
+        }
+
+        drop(data);
+
+        for entry in archive.entries {
+            let entry_type = detect_upload_type_of(&entry.path, None, &entry.data, &mut warnings)?;
+            // Nested archives are expanded by `expand_archive`; anything left
+            // that still looks like one is skipped rather than failing the upload
+            if entry_type == FileType::Zip {
+                warnings.push(format!("'{}': skipped nested archive '{}'", file.filename, entry.path));
+                continue;
+            }
+            let entry_file = UploadedFile::from_bytes(entry.path, None, &entry.data)?;
+            expanded_files.push((entry_file, entry_type));
+        }
+    }
+
+    if expanded_files.len() > MAX_FILES {
+        return Err(FileUploadError::TooManyFiles(MAX_FILES));
+    }
+
+    let total_size: usize = expanded_files.iter().map(|(file, _)| file.size).sum();
+    if total_size > MAX_TOTAL_SIZE {
+        return Err(FileUploadError::TotalSizeTooLarge(MAX_TOTAL_SIZE));
+    }
+
+    Ok((expanded_files, warnings))
+}
+
+/// Detect the type of an uploaded file from its contents `data`
+fn detect_upload_type(file: &UploadedFile, data: &[u8], warnings: &mut Vec<String>) -> Result<FileType, FileUploadError> {
+    detect_upload_type_of(&file.filename, file.content_type.as_deref(), data, warnings)
+}
+
+/// Detect the type of a file, recording extension/content mismatches
+fn detect_upload_type_of(
+    filename: &str,
+    content_type: Option<&str>,
+    data: &[u8],
+    warnings: &mut Vec<String>,
+) -> Result<FileType, FileUploadError> {
+    let detection = detect_file_type(data, filename, content_type)
+        .ok_or_else(|| FileUploadError::UnsupportedFileType(filename.to_string()))?;
+
+    if let Some(mismatch) = detection.mismatch {
+        warn!("File type mismatch: {}", mismatch);
+        warnings.push(mismatch);
+    }
+
+    Ok(detection.file_type)
 }
@@ -1,6 +1,7 @@
 //! HTTP server configuration
 
 use axum::{
+    extract::DefaultBodyLimit,
     routing::{get, post},
     Router,
 };
@@ -8,7 +9,7 @@ use std::net::SocketAddr;
 use tower_http::cors::{Any, CorsLayer};
 use tracing::info;
 
-use super::file_upload::{analyze_files_handler, health_handler};
+use super::file_upload::{analyze_files_handler, health_handler, MAX_REQUEST_SIZE};
 
 /// Creates the Axum router with all routes configured
 pub fn create_router() -> Router {
@@ -20,7 +21,8 @@ pub fn create_router() -> Router {
 
     Router::new()
         .route("/health", get(health_handler))
-        .route("/api/analyze", post(analyze_files_handler))
+        // Uploads are spooled to disk, so allow bodies up to the upload limits
+        .route("/api/analyze", post(analyze_files_handler).layer(DefaultBodyLimit::max(MAX_REQUEST_SIZE)))
         .layer(cors)
 }
 
@@ -0,0 +1,222 @@
+//! Command-line interface for offline analysis of local files
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use anyhow::{anyhow, bail, Context};
+
+use document_similarity_analyzer::core::{
+    analyze_sentence_similarity_with_options, analyze_sentence_similarity_within_budget, SentenceAnalysisOptions,
+    SentenceDocument,
+};
+use document_similarity_analyzer::extraction::{detect_file_type, expand_archive, extract_text, ArchiveLimits, FileType};
+use document_similarity_analyzer::models::ClusterMethod;
+use document_similarity_analyzer::report::{write_report, ReportFormat};
+use document_similarity_analyzer::sentence::{split_sentences_with_language, Language};
+
+const USAGE: &str = "\
+Usage:
+  document-similarity-analyzer                 Run the HTTP server (port from $PORT, default 3000)
+  document-similarity-analyzer report [OPTIONS] FILE...
+                                               Compare 2 or more documents; ZIP archives
+                                               are expanded into their documents
+
+Report options:
+  -o, --output PATH       Write the report to PATH, or to stdout for '-'
+                          (default: report.html, report.json, matches.csv, ...)
+  -f, --format FORMAT     html (default), json, csv (matches), global_csv,
+                          matrix_csv or ndjson (matches as JSON Lines)
+  -t, --threshold VALUE   Similarity threshold between 0.0 and 1.0 (default: 0.70)
+  -l, --language CODE     Language hint for sentence splitting (default: auto)
+  -k, --limit N           Report only the N most similar sentence pairs
+      --offset N          Skip the N most similar sentence pairs
+      --per-pair-limit N  Report at most N sentence pairs per document pair
+      --matrix            Include NxN document similarity matrices (json)
+      --cluster METHOD    Group documents: single, average, complete or components (json)
+      --cluster-threshold VALUE
+                          Minimum global similarity within a cluster (default: 0.50)
+      --memory-budget MB  Stop analyses that need more memory";
+
+/// Options of the `report` command
+struct ReportArgs {
+    files: Vec<PathBuf>,
+    /// Output path; defaults to the format's file name
+    output: Option<String>,
+    format: ReportFormat,
+    options: SentenceAnalysisOptions,
+    language: Language,
+    /// Peak-memory budget of the analysis in bytes
+    memory_budget: Option<usize>,
+}
+
+/// Run the `report` command with the arguments following it
+pub fn run_report(args: &[String]) -> anyhow::Result<()> {
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    let args = parse_report_args(args)?;
+    let start_time = Instant::now();
+
+    let mut warnings = Vec::new();
+    let mut documents = Vec::new();
+    for path in &args.files {
+        documents.extend(load_documents(path, args.language, &mut warnings)?);
+    }
+    if documents.len() < 2 {
+        bail!("at least 2 documents are required, found {}\n\n{}", documents.len(), USAGE);
+    }
+
+    let analysis = match args.memory_budget {
+        Some(budget) => analyze_sentence_similarity_within_budget(&documents, &args.options, budget)?,
+        None => analyze_sentence_similarity_with_options(&documents, &args.options),
+    };
+    let processing_time_ms = start_time.elapsed().as_millis() as u64;
+    let response = analysis.into_response(&documents, args.options.threshold, processing_time_ms, warnings);
+
+    let output = args.output.as_deref().unwrap_or(args.format.file_name());
+    if output == "-" {
+        let mut stdout = io::stdout().lock();
+        write_report(args.format, &response, &documents, &mut stdout)?;
+        stdout.flush()?;
+    } else {
+        let file = File::create(output).with_context(|| format!("cannot write '{}'", output))?;
+        let mut writer = BufWriter::new(file);
+        write_report(args.format, &response, &documents, &mut writer)
+            .and_then(|_| writer.flush())
+            .with_context(|| format!("cannot write '{}'", output))?;
+        eprintln!("Report written to {}", output);
+    }
+
+    Ok(())
+}
+
+fn parse_report_args(args: &[String]) -> anyhow::Result<ReportArgs> {
+    let mut parsed = ReportArgs {
+        files: Vec::new(),
+        output: None,
+        format: ReportFormat::Html,
+        options: SentenceAnalysisOptions::default(),
+        language: Language::Auto,
+        memory_budget: None,
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .cloned()
+                .ok_or_else(|| anyhow!("missing value for {}\n\n{}", name, USAGE))
+        };
+
+        match arg.as_str() {
+            "-o" | "--output" => parsed.output = Some(value(arg)?),
+            "-f" | "--format" => {
+                let name = value(arg)?;
+                parsed.format = ReportFormat::from_name(&name).ok_or_else(|| anyhow!("unknown format '{}'", name))?;
+            }
+            "-t" | "--threshold" => {
+                let threshold: f32 = value(arg)?.parse().context("threshold must be a number")?;
+                if !(0.0..=1.0).contains(&threshold) {
+                    bail!("threshold {} out of range, must be between 0.0 and 1.0", threshold);
+                }
+                parsed.options.threshold = threshold;
+            }
+            "-k" | "--limit" | "--offset" | "--per-pair-limit" => {
+                let count: usize = value(arg)?
+                    .parse()
+                    .with_context(|| format!("{} must be a non-negative integer", arg))?;
+                match arg.as_str() {
+                    "--offset" => parsed.options.offset = count,
+                    "--per-pair-limit" => parsed.options.per_document_pair_limit = Some(count),
+                    _ => parsed.options.limit = Some(count),
+                }
+            }
+            "--matrix" => parsed.options.similarity_matrices = true,
+            "--cluster" => {
+                let name = value(arg)?;
+                parsed.options.clustering =
+                    Some(ClusterMethod::from_name(&name).ok_or_else(|| anyhow!("unknown clustering method '{}'", name))?);
+            }
+            "--cluster-threshold" => {
+                let threshold: f32 = value(arg)?.parse().context("cluster threshold must be a number")?;
+                if !(0.0..=1.0).contains(&threshold) {
+                    bail!("cluster threshold {} out of range, must be between 0.0 and 1.0", threshold);
+                }
+                parsed.options.cluster_threshold = threshold;
+            }
+            "--memory-budget" => {
+                let megabytes: usize = value(arg)?
+                    .parse()
+                    .context("memory budget must be a whole number of megabytes")?;
+                parsed.memory_budget = Some(megabytes.saturating_mul(1024 * 1024));
+            }
+            "-l" | "--language" => {
+                let code = value(arg)?;
+                parsed.language = Language::from_code(&code).ok_or_else(|| anyhow!("unknown language '{}'", code))?;
+            }
+            flag if flag.starts_with('-') => bail!("unknown option '{}'\n\n{}", flag, USAGE),
+            file => parsed.files.push(PathBuf::from(file)),
+        }
+    }
+
+    if parsed.files.is_empty() {
+        bail!("at least 1 file is required\n\n{}", USAGE);
+    }
+
+    Ok(parsed)
+}
+
+/// Read a local file into its documents: the file itself, or each supported
+/// entry of a ZIP archive, expanded like an upload to the server
+fn load_documents(path: &Path, language: Language, warnings: &mut Vec<String>) -> anyhow::Result<Vec<SentenceDocument>> {
+    let filename = path.display().to_string();
+    let data = std::fs::read(path).with_context(|| format!("cannot read '{}'", filename))?;
+
+    let file_type = detect_type(&filename, &data, warnings)?;
+    if file_type != FileType::Zip {
+        return Ok(vec![load_document(filename, &data, file_type, language)?]);
+    }
+
+    let archive = expand_archive(&data, &ArchiveLimits::default()).map_err(|e| anyhow!("'{}': {}", filename, e))?;
+    if !archive.skipped.is_empty() {
+        warnings.push(format!(
+            "'{}': skipped {} unsupported entries: {}",
+            filename,
+            archive.skipped.len(),
+            archive.skipped.join(", ")
+        ));
+    }
+
+    let mut documents = Vec::new();
+    for entry in archive.entries {
+        let entry_type = detect_type(&entry.path, &entry.data, warnings)?;
+        if entry_type == FileType::Zip {
+            warnings.push(format!("'{}': skipped nested archive '{}'", filename, entry.path));
+            continue;
+        }
+        documents.push(load_document(entry.path, &entry.data, entry_type, language)?);
+    }
+    Ok(documents)
+}
+
+/// Detect the type of a file, recording extension/content mismatches
+fn detect_type(filename: &str, data: &[u8], warnings: &mut Vec<String>) -> anyhow::Result<FileType> {
+    let detection = detect_file_type(data, filename, None).ok_or_else(|| anyhow!("'{}': unsupported file type", filename))?;
+    warnings.extend(detection.mismatch);
+    Ok(detection.file_type)
+}
+
+/// Extract and split one document
+fn load_document(filename: String, data: &[u8], file_type: FileType, language: Language) -> anyhow::Result<SentenceDocument> {
+    let text = extract_text(data, file_type).map_err(|e| anyhow!("'{}': {}", filename, e))?;
+    let sentences = split_sentences_with_language(&text, language);
+    if sentences.is_empty() {
+        bail!("'{}' contains no text or sentences", filename);
+    }
+
+    Ok(SentenceDocument::new(filename, sentences))
+}
@@ -0,0 +1,271 @@
+//! Sentence similarity blocks and one-to-one alignment between two documents
+
+use crate::models::descending_similarity;
+
+/// Similarities between every sentence of one document (rows) and every
+/// sentence of another (columns), stored row-major
+#[derive(Debug, Clone)]
+pub(crate) struct SimilarityBlock {
+    rows: usize,
+    cols: usize,
+    values: Vec<f32>,
+}
+
+impl SimilarityBlock {
+    /// Build a block from row-major values; `values.len()` must be `rows * cols`
+    pub(crate) fn new(rows: usize, cols: usize, values: Vec<f32>) -> Self {
+        debug_assert_eq!(values.len(), rows * cols);
+        Self { rows, cols, values }
+    }
+
+    pub(crate) fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub(crate) fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub(crate) fn get(&self, row: usize, col: usize) -> f32 {
+        self.values[row * self.cols + col]
+    }
+
+    /// Mean over all sentence pairs
+    pub(crate) fn mean(&self) -> f32 {
+        self.values.iter().sum::<f32>() / self.values.len() as f32
+    }
+
+    /// Best similarity of every row sentence
+    pub(crate) fn row_max(&self) -> Vec<f32> {
+        self.values
+            .chunks(self.cols)
+            .map(|row| row.iter().copied().fold(0.0, f32::max))
+            .collect()
+    }
+
+    /// Best similarity of every column sentence
+    pub(crate) fn col_max(&self) -> Vec<f32> {
+        (0..self.cols)
+            .map(|col| (0..self.rows).map(|row| self.get(row, col)).fold(0.0, f32::max))
+            .collect()
+    }
+
+    /// Mean of the per-sentence best similarity over the sentences of both documents
+    pub(crate) fn best_match_mean(&self) -> f32 {
+        let total: f32 = self.row_max().iter().sum::<f32>() + self.col_max().iter().sum::<f32>();
+        total / (self.rows + self.cols) as f32
+    }
+
+    /// Score of a one-to-one alignment: twice the summed similarity of the
+    /// aligned pairs over the sentence count of both documents, so that two
+    /// identical documents score 1.0 and unaligned sentences count as 0
+    pub(crate) fn alignment_score(&self, pairs: &[(usize, usize)]) -> f32 {
+        let total: f32 = pairs.iter().map(|&(row, col)| self.get(row, col)).sum();
+        2.0 * total / (self.rows + self.cols) as f32
+    }
+}
+
+/// Greedy one-to-one alignment: repeatedly pair the most similar remaining
+/// sentences. Pairs with similarity 0 are not aligned. Returns `(row, col)`
+/// pairs sorted by row.
+pub(crate) fn greedy_alignment(block: &SimilarityBlock) -> Vec<(usize, usize)> {
+    let mut candidates: Vec<(usize, usize)> = (0..block.rows())
+        .flat_map(|row| (0..block.cols()).map(move |col| (row, col)))
+        .filter(|&(row, col)| block.get(row, col) > 0.0)
+        .collect();
+    candidates.sort_by(|&(ra, ca), &(rb, cb)| {
+        descending_similarity(block.get(ra, ca), block.get(rb, cb)).then((ra, ca).cmp(&(rb, cb)))
+    });
+
+    let mut row_used = vec![false; block.rows()];
+    let mut col_used = vec![false; block.cols()];
+    let mut pairs = Vec::new();
+
+    for (row, col) in candidates {
+        if !row_used[row] && !col_used[col] {
+            row_used[row] = true;
+            col_used[col] = true;
+            pairs.push((row, col));
+        }
+    }
+
+    pairs.sort_unstable();
+    pairs
+}
+
+/// Largest number of sentences on either side of the assignment solved
+/// exactly by `bipartite_alignment`; the Hungarian algorithm is cubic
+pub(crate) const MAX_BIPARTITE_SENTENCES: usize = 500;
+
+/// Maximum-weight one-to-one alignment (Hungarian algorithm)
+///
+/// Only pairs with similarity at or above `threshold` carry weight, so the
+/// result maximizes the total similarity of above-threshold pairs. Sentences
+/// without such a pair are left out of the assignment. When more than
+/// `MAX_BIPARTITE_SENTENCES` remain on either side, the greedy alignment is
+/// used instead. Returns `(row, col)` pairs at or above the threshold,
+/// sorted by row.
+pub(crate) fn bipartite_alignment(block: &SimilarityBlock, threshold: f32) -> Vec<(usize, usize)> {
+    let is_edge = |row: usize, col: usize| {
+        let similarity = block.get(row, col);
+        similarity >= threshold && similarity > 0.0
+    };
+    let rows: Vec<usize> = (0..block.rows())
+        .filter(|&row| (0..block.cols()).any(|col| is_edge(row, col)))
+        .collect();
+    let cols: Vec<usize> = (0..block.cols())
+        .filter(|&col| rows.iter().any(|&row| is_edge(row, col)))
+        .collect();
+
+    if rows.len().max(cols.len()) > MAX_BIPARTITE_SENTENCES {
+        // Greedy takes pairs best first, so below-threshold pairs never
+        // displace one above it
+        return greedy_alignment(block)
+            .into_iter()
+            .filter(|&(row, col)| is_edge(row, col))
+            .collect();
+    }
+
+    let weight = |i: usize, j: usize| {
+        let (row, col) = (rows[i], cols[j]);
+        if is_edge(row, col) {
+            block.get(row, col) as f64
+        } else {
+            0.0
+        }
+    };
+
+    // The algorithm below needs at most as many rows as columns
+    let transposed = rows.len() > cols.len();
+    let (n, m) = if transposed {
+        (cols.len(), rows.len())
+    } else {
+        (rows.len(), cols.len())
+    };
+    let cost = |i: usize, j: usize| {
+        if transposed {
+            -weight(j, i)
+        } else {
+            -weight(i, j)
+        }
+    };
+
+    let mut pairs: Vec<(usize, usize)> = hungarian(n, m, cost)
+        .into_iter()
+        .map(|(i, j)| if transposed { (rows[j], cols[i]) } else { (rows[i], cols[j]) })
+        .filter(|&(row, col)| is_edge(row, col))
+        .collect();
+
+    pairs.sort_unstable();
+    pairs
+}
+
+/// Minimum-cost assignment of `n` rows to `m >= n` columns
+///
+/// Classic O(n²·m) Kuhn-Munkres with potentials; returns `(row, col)` pairs.
+fn hungarian(n: usize, m: usize, cost: impl Fn(usize, usize) -> f64) -> Vec<(usize, usize)> {
+    // 1-based indices; column 0 and row 0 are sentinels
+    let mut u = vec![0.0f64; n + 1];
+    let mut v = vec![0.0f64; m + 1];
+    let mut assigned_row = vec![0usize; m + 1];
+    let mut way = vec![0usize; m + 1];
+
+    for row in 1..=n {
+        assigned_row[0] = row;
+        let mut col0 = 0usize;
+        let mut min_value = vec![f64::INFINITY; m + 1];
+        let mut used = vec![false; m + 1];
+
+        loop {
+            used[col0] = true;
+            let row0 = assigned_row[col0];
+            let mut delta = f64::INFINITY;
+            let mut col1 = 0usize;
+
+            for col in 1..=m {
+                if used[col] {
+                    continue;
+                }
+                let reduced = cost(row0 - 1, col - 1) - u[row0] - v[col];
+                if reduced < min_value[col] {
+                    min_value[col] = reduced;
+                    way[col] = col0;
+                }
+                if min_value[col] < delta {
+                    delta = min_value[col];
+                    col1 = col;
+                }
+            }
+
+            for col in 0..=m {
+                if used[col] {
+                    u[assigned_row[col]] += delta;
+                    v[col] -= delta;
+                } else {
+                    min_value[col] -= delta;
+                }
+            }
+
+            col0 = col1;
+            if assigned_row[col0] == 0 {
+                break;
+            }
+        }
+
+        // Augment along the alternating path
+        loop {
+            let col1 = way[col0];
+            assigned_row[col0] = assigned_row[col1];
+            col0 = col1;
+            if col0 == 0 {
+                break;
+            }
+        }
+    }
+
+    (1..=m)
+        .filter(|&col| assigned_row[col] != 0)
+        .map(|col| (assigned_row[col] - 1, col - 1))
+        .collect()
+}
+
+/// Order-preserving alignment, like a diff: the pairs maximize the total
+/// similarity subject to both documents being read front to back. Only pairs
+/// at or above `threshold` can be aligned. Returns `(row, col)` pairs sorted by row.
+pub(crate) fn monotonic_alignment(block: &SimilarityBlock, threshold: f32) -> Vec<(usize, usize)> {
+    let (rows, cols) = (block.rows(), block.cols());
+    let width = cols + 1;
+    let mut score = vec![0.0f32; (rows + 1) * width];
+
+    for row in 1..=rows {
+        for col in 1..=cols {
+            let skip = score[(row - 1) * width + col].max(score[row * width + col - 1]);
+            let similarity = block.get(row - 1, col - 1);
+            let align = if similarity >= threshold {
+                score[(row - 1) * width + col - 1] + similarity
+            } else {
+                f32::NEG_INFINITY
+            };
+            score[row * width + col] = skip.max(align);
+        }
+    }
+
+    // Walk back from the bottom-right corner
+    let mut pairs = Vec::new();
+    let (mut row, mut col) = (rows, cols);
+    while row > 0 && col > 0 {
+        let current = score[row * width + col];
+        if current == score[(row - 1) * width + col] {
+            row -= 1;
+        } else if current == score[row * width + col - 1] {
+            col -= 1;
+        } else {
+            pairs.push((row - 1, col - 1));
+            row -= 1;
+            col -= 1;
+        }
+    }
+
+    pairs.reverse();
+    pairs
+}
@@ -0,0 +1,184 @@
+//! Grouping documents from a similarity matrix
+
+use crate::models::{ClusterMethod, DendrogramMerge, DocumentCluster, DocumentClustering, SimilarityMatrix};
+
+/// Cluster the documents of a similarity matrix
+///
+/// Agglomerative methods build the full dendrogram and cut it where the
+/// linkage similarity drops below `threshold`; `Components` only links pairs
+/// at or above `threshold` and returns no dendrogram. Single linkage and
+/// components give the same groups.
+pub fn cluster_documents(matrix: &SimilarityMatrix, method: ClusterMethod, threshold: f32) -> DocumentClustering {
+    let n = matrix.index.len();
+    let (assignments, dendrogram) = match method {
+        ClusterMethod::Components => (connected_components(&matrix.matrix, threshold), Vec::new()),
+        ClusterMethod::Single | ClusterMethod::Average | ClusterMethod::Complete => {
+            let dendrogram = agglomerative_clustering(&matrix.matrix, method);
+            (cut_dendrogram(n, &dendrogram, threshold), dendrogram)
+        }
+    };
+
+    let cluster_count = assignments.iter().max().map_or(0, |max| max + 1);
+    let mut members: Vec<Vec<String>> = vec![Vec::new(); cluster_count];
+    for (document, &cluster) in assignments.iter().enumerate() {
+        members[cluster].push(matrix.index[document].clone());
+    }
+    let clusters = members
+        .into_iter()
+        .enumerate()
+        .filter(|(_, documents)| documents.len() > 1)
+        .map(|(id, documents)| DocumentCluster { id, documents })
+        .collect();
+
+    DocumentClustering {
+        method,
+        threshold,
+        assignments,
+        clusters,
+        dendrogram,
+    }
+}
+
+/// Agglomerative hierarchical clustering with the Lance-Williams update
+///
+/// Returns the `n - 1` merges in order. Distances are `1 - similarity`; ties
+/// merge the pair of lowest indices first, so the result is deterministic.
+/// `Components` is treated as single linkage.
+pub fn agglomerative_clustering(similarity: &[Vec<f32>], method: ClusterMethod) -> Vec<DendrogramMerge> {
+    let n = similarity.len();
+    if n < 2 {
+        return Vec::new();
+    }
+
+    let mut distance: Vec<Vec<f32>> = similarity
+        .iter()
+        .map(|row| row.iter().map(|s| 1.0 - s).collect())
+        .collect();
+    // Node id and size of the cluster held in each slot; merged-away slots are None
+    let mut clusters: Vec<Option<(usize, usize)>> = (0..n).map(|i| Some((i, 1))).collect();
+    let mut merges = Vec::with_capacity(n - 1);
+
+    for step in 0..n - 1 {
+        // Closest pair of active clusters
+        let mut closest: Option<(usize, usize, f32)> = None;
+        for i in 0..n {
+            if clusters[i].is_none() {
+                continue;
+            }
+            for j in (i + 1)..n {
+                if clusters[j].is_none() {
+                    continue;
+                }
+                if closest.is_none_or(|(_, _, best)| distance[i][j] < best) {
+                    closest = Some((i, j, distance[i][j]));
+                }
+            }
+        }
+        let Some((i, j, height)) = closest else { break };
+        let ((node_i, size_i), (node_j, size_j)) = (clusters[i].unwrap(), clusters[j].unwrap());
+
+        merges.push(DendrogramMerge {
+            left: node_i.min(node_j),
+            right: node_i.max(node_j),
+            similarity: 1.0 - height,
+            height,
+            size: size_i + size_j,
+        });
+
+        // Slot i holds the merged cluster, slot j is retired
+        for k in 0..n {
+            if k == i || k == j || clusters[k].is_none() {
+                continue;
+            }
+            let merged = match method {
+                ClusterMethod::Single | ClusterMethod::Components => distance[i][k].min(distance[j][k]),
+                ClusterMethod::Complete => distance[i][k].max(distance[j][k]),
+                ClusterMethod::Average => {
+                    (size_i as f32 * distance[i][k] + size_j as f32 * distance[j][k]) / (size_i + size_j) as f32
+                }
+            };
+            distance[i][k] = merged;
+            distance[k][i] = merged;
+        }
+        clusters[i] = Some((n + step, size_i + size_j));
+        clusters[j] = None;
+    }
+
+    merges
+}
+
+/// Cluster of each of the `n` leaves after applying the merges whose
+/// similarity is at least `threshold`
+pub fn cut_dendrogram(n: usize, merges: &[DendrogramMerge], threshold: f32) -> Vec<usize> {
+    let mut sets = DisjointSets::new(n);
+    // Leaf member of every node, to union merged nodes through their leaves
+    let mut representative: Vec<usize> = (0..n).collect();
+
+    for merge in merges {
+        let (left, right) = (representative[merge.left], representative[merge.right]);
+        representative.push(left);
+        if merge.similarity >= threshold {
+            sets.union(left, right);
+        }
+    }
+
+    sets.labels()
+}
+
+/// Connected components of the graph linking pairs with similarity at least `threshold`
+pub fn connected_components(similarity: &[Vec<f32>], threshold: f32) -> Vec<usize> {
+    let n = similarity.len();
+    let mut sets = DisjointSets::new(n);
+
+    for (i, row) in similarity.iter().enumerate() {
+        for (j, &value) in row.iter().enumerate().skip(i + 1) {
+            if value >= threshold {
+                sets.union(i, j);
+            }
+        }
+    }
+
+    sets.labels()
+}
+
+/// Union-find over `0..n`
+struct DisjointSets {
+    parent: Vec<usize>,
+}
+
+impl DisjointSets {
+    fn new(n: usize) -> Self {
+        Self { parent: (0..n).collect() }
+    }
+
+    fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a != b {
+            self.parent[a.max(b)] = a.min(b);
+        }
+    }
+
+    /// Set label of every element, numbered by first element
+    fn labels(&mut self) -> Vec<usize> {
+        let mut label_of_root: Vec<Option<usize>> = vec![None; self.parent.len()];
+        let mut next = 0;
+
+        (0..self.parent.len())
+            .map(|x| {
+                let root = self.find(x);
+                *label_of_root[root].get_or_insert_with(|| {
+                    next += 1;
+                    next - 1
+                })
+            })
+            .collect()
+    }
+}
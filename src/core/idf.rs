@@ -4,6 +4,8 @@ use std::collections::HashMap;
 
 /// Computes Inverse Document Frequency (IDF) across all documents.
 /// Uses smoothed IDF: IDF = log((N + 1) / (df + 1)) + 1
+/// Document frequencies are integer counts, so the result does not depend
+/// on map iteration order.
 pub fn compute_idf(tfs: &[HashMap<String, f32>]) -> HashMap<String, f32> {
     if tfs.is_empty() {
         return HashMap::new();
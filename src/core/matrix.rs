@@ -1,33 +1,192 @@
 //! Similarity Matrix generation - parallel computation
 
-use super::cosine_similarity;
+use super::{dot_product, l2_normalize};
 use rayon::prelude::*;
 
-pub fn compute_similarity_matrix(vectors: &[Vec<f32>]) -> Vec<Vec<f32>> {
-    let n = vectors.len(); //TODO penamaan variabel
+pub use half::f16;
 
-    if n == 0 {
-        return vec![];
-    }
-
-    // Parallel computation of similarity matrix
-    (0..n)
-        .into_par_iter()
-        .map(|i| {
-            (0..n)
-                .map(|j| {
-                    if i == j {
-                        1.0 // Diagonal is always 1.0
-                    } else if i < j {
-                        // Compute similarity for upper triangle
-                        cosine_similarity(&vectors[i], &vectors[j])
-                    } else {
-                        // For lower triangle, we'll compute it too
-                        // (could optimize by computing upper only and copying)
-                        cosine_similarity(&vectors[i], &vectors[j])
+/// Side of the square tiles the upper triangle is computed and mirrored in
+const TILE: usize = 64;
+
+/// Element type of a `DenseMatrix`: `f32`, or `f16` to halve the memory of
+/// huge matrices at about three significant digits
+pub trait MatrixElement: Copy + Default + Send + Sync + 'static {
+    const ONE: Self;
+
+    fn from_f32(value: f32) -> Self;
+
+    fn to_f32(self) -> f32;
+}
+
+impl MatrixElement for f32 {
+    const ONE: Self = 1.0;
+
+    fn from_f32(value: f32) -> Self {
+        value
+    }
+
+    fn to_f32(self) -> f32 {
+        self
+    }
+}
+
+impl MatrixElement for f16 {
+    const ONE: Self = f16::ONE;
+
+    fn from_f32(value: f32) -> Self {
+        f16::from_f32(value)
+    }
+
+    fn to_f32(self) -> f32 {
+        f16::to_f32(self)
+    }
+}
+
+/// Square matrix stored contiguously in row-major order
+#[derive(Debug, Clone, PartialEq)]
+pub struct DenseMatrix<T = f32> {
+    size: usize,
+    values: Vec<T>,
+}
+
+impl<T: MatrixElement> DenseMatrix<T> {
+    /// `size` x `size` matrix of zeros
+    pub fn zeros(size: usize) -> Self {
+        Self {
+            size,
+            values: vec![T::default(); size * size],
+        }
+    }
+
+    /// Number of rows (and columns)
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Value at row `i`, column `j`, widened to `f32`
+    pub fn get(&self, i: usize, j: usize) -> f32 {
+        self.values[i * self.size + j].to_f32()
+    }
+
+    /// Row `i` as stored
+    pub fn row(&self, i: usize) -> &[T] {
+        &self.values[i * self.size..(i + 1) * self.size]
+    }
+
+    /// All values, row after row
+    pub fn as_slice(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Rows as nested `f32` vectors (e.g. for `SimilarityMatrix`)
+    pub fn to_rows(&self) -> Vec<Vec<f32>> {
+        self.values
+            .chunks(self.size.max(1))
+            .take(self.size)
+            .map(|row| row.iter().map(|value| value.to_f32()).collect())
+            .collect()
+    }
+
+    /// Copy the upper triangle onto the lower one, tile by tile so both the
+    /// rows read and the columns written stay in cache
+    fn mirror_upper(&mut self) {
+        let n = self.size;
+        for tile_row in (0..n).step_by(TILE) {
+            for tile_col in (tile_row..n).step_by(TILE) {
+                for i in tile_row..(tile_row + TILE).min(n) {
+                    for j in tile_col.max(i + 1)..(tile_col + TILE).min(n) {
+                        self.values[j * n + i] = self.values[i * n + j];
                     }
-                })
-                .collect()
+                }
+            }
+        }
+    }
+}
+
+/// Cosine similarity matrix of dense vectors (e.g. `vectorize` output)
+///
+/// Each vector is normalized once, so every pair costs a single dot product.
+pub fn compute_similarity_matrix(vectors: &[Vec<f32>]) -> DenseMatrix<f32> {
+    compute_similarity_matrix_as(vectors)
+}
+
+/// `compute_similarity_matrix` with a choice of output element type, e.g.
+/// `compute_similarity_matrix_as::<f16>` for huge N
+pub fn compute_similarity_matrix_as<T: MatrixElement>(vectors: &[Vec<f32>]) -> DenseMatrix<T> {
+    let normalized: Vec<Vec<f32>> = vectors
+        .par_iter()
+        .map(|vector| {
+            let mut vector = vector.clone();
+            l2_normalize(&mut vector);
+            vector
         })
-        .collect()
+        .collect();
+
+    compute_normalized_similarity_matrix(&normalized)
+}
+
+/// Similarity matrix of vectors that are already L2-normalized
+/// (e.g. `vectorize_normalized` output)
+pub fn compute_normalized_similarity_matrix<T: MatrixElement>(vectors: &[Vec<f32>]) -> DenseMatrix<T> {
+    symmetric_dense_matrix(vectors.len(), |i, j| {
+        // Keep `cosine_similarity`'s 0.0 for empty vectors
+        if vectors[i].is_empty() {
+            0.0
+        } else {
+            dot_product(&vectors[i], &vectors[j])
+        }
+    })
+}
+
+/// Build a symmetric NxN matrix with a diagonal of 1.0
+///
+/// `similarity(i, j)` is only called for the upper triangle (`i < j`), in
+/// parallel, and each value is mirrored to `[j][i]`.
+pub(crate) fn symmetric_matrix<F>(n: usize, similarity: F) -> Vec<Vec<f32>>
+where
+    F: Fn(usize, usize) -> f32 + Sync,
+{
+    symmetric_dense_matrix::<f32, F>(n, similarity).to_rows()
+}
+
+/// `symmetric_matrix` into a contiguous matrix
+///
+/// Rows are split into bands computed in parallel, each band writing only
+/// its own rows. Within a band the upper triangle is walked in column tiles
+/// of `TILE`, so the same few vectors are reused while they are in cache.
+pub(crate) fn symmetric_dense_matrix<T, F>(n: usize, similarity: F) -> DenseMatrix<T>
+where
+    T: MatrixElement,
+    F: Fn(usize, usize) -> f32 + Sync,
+{
+    let mut matrix = DenseMatrix::zeros(n);
+    if n == 0 {
+        return matrix;
+    }
+
+    // Thinner bands than TILE for small N, so every thread gets some
+    let band = TILE.min(n.div_ceil(rayon::current_num_threads() * 4)).max(1);
+    matrix
+        .values
+        .par_chunks_mut(band * n)
+        .enumerate()
+        .for_each(|(band_index, rows)| {
+            let first_row = band_index * band;
+            for (offset, row) in rows.chunks_mut(n).enumerate() {
+                row[first_row + offset] = T::ONE;
+            }
+
+            for tile_col in (first_row..n).step_by(TILE) {
+                let tile_end = (tile_col + TILE).min(n);
+                for (offset, row) in rows.chunks_mut(n).enumerate() {
+                    let i = first_row + offset;
+                    for (j, value) in row.iter_mut().enumerate().take(tile_end).skip(tile_col.max(i + 1)) {
+                        *value = T::from_f32(similarity(i, j));
+                    }
+                }
+            }
+        });
+
+    matrix.mirror_upper();
+    matrix
 }
@@ -0,0 +1,151 @@
+//! Peak-memory estimate of a sentence analysis, checked against a budget
+
+use std::mem::size_of;
+
+use rayon::prelude::*;
+use thiserror::Error;
+
+use super::alignment::MAX_BIPARTITE_SENTENCES;
+use super::sentence_pipeline::{AlignmentMode, SentenceAnalysisOptions, SentenceDocument};
+use super::{normalize_text, tokenize};
+use crate::models::AggregationStrategy;
+
+/// Bytes per term entry of a sentence: its id, TF value and TF-IDF value
+const BYTES_PER_TERM: usize = size_of::<u32>() * 3 + size_of::<f32>() * 2;
+/// Bytes per sentence vector besides its entries
+const BYTES_PER_SENTENCE: usize = 96;
+/// Bytes per sentence of the Hungarian algorithm: the active-sentence index
+/// and its potentials, path and slack entries
+const HUNGARIAN_BYTES_PER_SENTENCE: usize = 6 * size_of::<u64>();
+const MEGABYTE: f64 = 1024.0 * 1024.0;
+
+/// The estimated memory of an analysis exceeds the budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error(
+    "analysis needs an estimated {:.1} MB, over the memory budget of {:.1} MB",
+    *.estimated as f64 / MEGABYTE,
+    *.budget as f64 / MEGABYTE
+)]
+pub struct MemoryBudgetExceeded {
+    pub estimated: usize,
+    pub budget: usize,
+}
+
+/// Estimated peak memory of an analysis, in bytes, by stage
+///
+/// Computed from sentence and token counts before any work is done. Matches
+/// are not included: without `limit` their number depends on the threshold,
+/// so `analyze_sentence_similarity_within_budget` counts them as they are kept.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryEstimate {
+    /// Sentence text of the documents
+    pub text: usize,
+    /// Normalized tokens of every sentence
+    pub tokens: usize,
+    /// Term ids and TF-IDF vectors
+    pub vectors: usize,
+    /// Similarity blocks of the document pairs compared at the same time
+    pub similarity_blocks: usize,
+    /// Alignment buffers of those pairs (with `alignment` or the `alignment`
+    /// aggregation)
+    pub alignment: usize,
+    /// NxN document matrices (with `similarity_matrices` or `clustering`)
+    pub matrices: usize,
+}
+
+impl MemoryEstimate {
+    pub fn total(&self) -> usize {
+        self.text + self.tokens + self.vectors + self.similarity_blocks + self.alignment + self.matrices
+    }
+
+    /// Error when the estimate exceeds `budget` bytes
+    pub fn check(&self, budget: usize) -> Result<(), MemoryBudgetExceeded> {
+        let estimated = self.total();
+        if estimated > budget {
+            return Err(MemoryBudgetExceeded { estimated, budget });
+        }
+        Ok(())
+    }
+}
+
+/// Estimate the peak memory of analyzing `documents` with `options`
+///
+/// Tokenizes every sentence to count its tokens; the budgeted analyses
+/// check the estimate from the tokens they make anyway.
+pub fn estimate_analysis_memory(documents: &[SentenceDocument], options: &SentenceAnalysisOptions) -> MemoryEstimate {
+    // Count tokens the way the pipeline makes them: CJK text yields a bigram
+    // per character, far more than its whitespace-separated words
+    let words: usize = documents
+        .par_iter()
+        .flat_map(|doc| &doc.sentences)
+        .map(|sentence| tokenize(&normalize_text(sentence)).len())
+        .sum();
+
+    estimate_memory_with_tokens(documents, words, options)
+}
+
+/// `estimate_analysis_memory` given the number of tokens of all sentences
+pub(crate) fn estimate_memory_with_tokens(
+    documents: &[SentenceDocument],
+    words: usize,
+    options: &SentenceAnalysisOptions,
+) -> MemoryEstimate {
+    let sentence_counts: Vec<usize> = documents.iter().map(|doc| doc.sentences.len()).collect();
+    let sentences: usize = sentence_counts.iter().sum();
+    let text: usize = documents
+        .iter()
+        .flat_map(|doc| &doc.sentences)
+        .map(|sentence| sentence.len() + size_of::<String>())
+        .sum();
+
+    // Pairs run in parallel; at worst every thread holds a block as large as
+    // the one of the two longest documents
+    let mut longest = sentence_counts.clone();
+    longest.sort_unstable_by(|a, b| b.cmp(a));
+    let (a, b) = longest.first().zip(longest.get(1)).map_or((0, 0), |(&a, &b)| (a, b));
+    let pairs = documents.len() * documents.len().saturating_sub(1) / 2;
+    let concurrent_pairs = pairs.min(rayon::current_num_threads());
+    let similarity_blocks = a * b * size_of::<f32>() * concurrent_pairs;
+    let alignment = alignment_buffers(a, b, options) * concurrent_pairs;
+
+    let matrices = if options.similarity_matrices || options.clustering.is_some() {
+        // Sentence and document matrices, plus the clustering distances
+        3 * documents.len() * documents.len() * size_of::<f32>()
+    } else {
+        0
+    };
+
+    MemoryEstimate {
+        text,
+        // Token strings hold about as many bytes as the text itself
+        tokens: words * size_of::<String>() + text,
+        vectors: words * BYTES_PER_TERM + sentences * BYTES_PER_SENTENCE,
+        similarity_blocks,
+        alignment,
+        matrices,
+    }
+}
+
+/// Bytes of the largest alignment buffers of an `a` × `b` sentence pair
+fn alignment_buffers(a: usize, b: usize, options: &SentenceAnalysisOptions) -> usize {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+
+    // Greedy alignment sorts every nonzero cell of the block
+    let greedy = a * b * size_of::<(usize, usize)>();
+    let matching = match options.alignment {
+        AlignmentMode::Off => 0,
+        AlignmentMode::Bipartite if a.max(b) > MAX_BIPARTITE_SENTENCES => greedy,
+        AlignmentMode::Bipartite => (a + b + 2) * HUNGARIAN_BYTES_PER_SENTENCE,
+        AlignmentMode::Monotonic => (a + 1) * (b + 1) * size_of::<f32>(),
+    };
+    let aggregation = if options.aggregation == AggregationStrategy::Alignment {
+        greedy
+    } else {
+        0
+    };
+
+    // A pair aligns its matches, then aggregates; the buffers never coexist
+    matching.max(aggregation)
+}
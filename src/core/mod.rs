@@ -6,14 +6,31 @@ mod tf;
 mod idf;
 mod vectorize;
 mod similarity;
+mod sparse;
 mod matrix;
+mod memory;
+mod clustering;
+mod alignment;
+mod top_k;
 mod sentence_pipeline;
 
 pub use normalize::normalize_text;
-pub use tokenize::tokenize;
+pub use tokenize::{tokenize, tokenize_with_mode, TokenizerMode};
 pub use tf::compute_tf;
 pub use idf::compute_idf;
-pub use vectorize::{vectorize, compute_tfidf_vector};
-pub use similarity::{cosine_similarity, compute_cosine_similarity};
-pub use matrix::compute_similarity_matrix;
-pub use sentence_pipeline::{analyze_sentence_similarity, SentenceDocument};
+pub use vectorize::{
+    compute_normalized_tfidf_vector, compute_tfidf_vector, l2_normalize, vectorize, vectorize_normalized,
+};
+pub use similarity::{compute_cosine_similarity, compute_dot_product, cosine_similarity, dot_product};
+pub use sparse::{compute_sparse_idf, SparseVector, Vocabulary};
+pub use matrix::{
+    compute_normalized_similarity_matrix, compute_similarity_matrix, compute_similarity_matrix_as, f16,
+    DenseMatrix, MatrixElement,
+};
+pub use memory::{estimate_analysis_memory, MemoryBudgetExceeded, MemoryEstimate};
+pub use clustering::{agglomerative_clustering, cluster_documents, connected_components, cut_dendrogram};
+pub use sentence_pipeline::{
+    analyze_sentence_similarity, analyze_sentence_similarity_streaming, analyze_sentence_similarity_streaming_until,
+    analyze_sentence_similarity_streaming_within_budget, analyze_sentence_similarity_with_options, analyze_sentence_similarity_within_budget, AlignmentMode,
+    QuoteMode, SentenceAnalysis, SentenceAnalysisOptions, SentenceDocument,
+};
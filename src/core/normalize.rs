@@ -2,14 +2,17 @@
 
 /// Normalizes text by converting to lowercase, removing punctuation,
 /// and collapsing multiple whitespace into single space.
-
+///
+/// Besides ASCII punctuation this removes general punctuation (curly quotes,
+/// dashes, ellipsis), CJK and fullwidth punctuation (`。`, `、`, `！`, `「」`),
+/// Arabic punctuation (`،`, `؛`, `؟`) and the Devanagari danda (`।`, `॥`).
 pub fn normalize_text(text: &str) -> String {
     text.chars()
-        .map(|c| {
-            if c.is_ascii_punctuation() {
-                ' '
+        .flat_map(|c| {
+            if is_punctuation(c) {
+                ' '.to_lowercase()
             } else {
-                c.to_ascii_lowercase()
+                c.to_lowercase()
             }
         })
         .collect::<String>()
@@ -17,3 +20,23 @@ pub fn normalize_text(text: &str) -> String {
         .collect::<Vec<&str>>()
         .join(" ")
 }
+
+fn is_punctuation(c: char) -> bool {
+    c.is_ascii_punctuation()
+        || matches!(
+            c,
+            '¡' | '«' | '»' | '¿' | '·'
+                | '\u{2010}'..='\u{2027}'
+                | '\u{2030}'..='\u{205E}'
+                | '\u{3001}'..='\u{3003}'
+                | '\u{3008}'..='\u{3011}'
+                | '\u{3014}'..='\u{301F}'
+                | '\u{30FB}'
+                | '\u{FF01}'..='\u{FF0F}'
+                | '\u{FF1A}'..='\u{FF20}'
+                | '\u{FF3B}'..='\u{FF40}'
+                | '\u{FF5B}'..='\u{FF65}'
+                | '،' | '؛' | '؟' | '٪' | '۔'
+                | '।' | '॥'
+        )
+}
@@ -1,10 +1,28 @@
 //! Sentence-level document similarity analysis pipeline
 
-use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::mem::size_of;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
 use rayon::prelude::*;
 
-use crate::core::{compute_tf, compute_idf, normalize_text, tokenize, compute_tfidf_vector, compute_cosine_similarity};
-use crate::models::{SentenceMatch, GlobalSimilarity};
+use crate::core::{normalize_text, tokenize};
+use crate::core::alignment::{
+    bipartite_alignment, greedy_alignment, monotonic_alignment, SimilarityBlock,
+};
+use crate::core::clustering::cluster_documents;
+use crate::core::matrix::symmetric_matrix;
+use crate::core::memory::{estimate_memory_with_tokens, MemoryBudgetExceeded, MemoryEstimate};
+use crate::core::sparse::{compute_sparse_idf, SparseVector, Vocabulary};
+use crate::core::top_k::TopK;
+use crate::models::{
+    AggregationStrategy, AnalysisMetadata, BoilerplateSentence, ClusterMethod, DirectionalCoverage,
+    DocumentClustering, DocumentSummary, descending_similarity,
+    GlobalSimilarity, MatchCursor, SentenceAnalysisResponse, SentenceMatch, SimilarityMatrix,
+};
+use crate::sentence::{find_reference_section, has_citation, quoted_sentences};
 
 /// Represents a document with its sentences
 #[derive(Debug, Clone)]
@@ -19,189 +37,1125 @@ impl SentenceDocument {
     }
 }
 
-/// Represents a sentence with its TF-IDF vector
+/// Represents a sentence with its L2-normalized TF-IDF vector
 #[derive(Debug, Clone)]
 struct SentenceVector {
     doc_index: usize,
     sentence_index: usize,
-    vector: HashMap<String, f32>,
+    token_count: usize,
+    vector: SparseVector,
+}
+
+/// How quoted and cited sentences are treated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QuoteMode {
+    /// No quotation or citation detection
+    #[default]
+    Off,
+    /// Flag matches involving quoted or cited sentences
+    Mark,
+    /// Exclude quoted and cited sentences from matching
+    Exclude,
+}
+
+impl QuoteMode {
+    /// Parse a mode name: `off`, `mark` or `exclude`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "off" => Some(QuoteMode::Off),
+            "mark" => Some(QuoteMode::Mark),
+            "exclude" => Some(QuoteMode::Exclude),
+            _ => None,
+        }
+    }
+}
+
+/// Whether matches are restricted to a one-to-one sentence alignment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AlignmentMode {
+    /// Every sentence pair at or above the threshold is a match
+    #[default]
+    Off,
+    /// Maximum-weight bipartite matching: each sentence is matched at most once
+    Bipartite,
+    /// Order-preserving alignment, like a diff
+    Monotonic,
+}
+
+impl AlignmentMode {
+    /// Parse a mode name: `off`, `bipartite` or `monotonic`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "off" => Some(AlignmentMode::Off),
+            "bipartite" | "hungarian" => Some(AlignmentMode::Bipartite),
+            "monotonic" | "ordered" => Some(AlignmentMode::Monotonic),
+            _ => None,
+        }
+    }
+}
+
+/// Minimum number of documents a sentence must appear in to count as boilerplate,
+/// so that two documents sharing a sentence are never treated as boilerplate
+const MIN_BOILERPLATE_DOCUMENTS: usize = 3;
+
+/// Matches of a document pair handed over at a time, so that a large pair
+/// is never held in memory at once
+const MATCH_BATCH: usize = 1024;
+
+/// Quotation and citation flags of a sentence
+#[derive(Debug, Clone, Copy, Default)]
+struct Attribution {
+    quoted: bool,
+    cited: bool,
+}
+
+impl Attribution {
+    fn any(self) -> bool {
+        self.quoted || self.cited
+    }
+}
+
+/// Options for sentence-level analysis
+#[derive(Debug, Clone, PartialEq)]
+pub struct SentenceAnalysisOptions {
+    /// Minimum similarity for a sentence pair to be reported as a match
+    pub threshold: f32,
+    /// Sentences with fewer tokens (after normalization) are excluded from matching
+    pub min_tokens: usize,
+    /// Sentences with fewer characters are excluded from matching
+    pub min_chars: usize,
+    /// Treatment of quoted and cited sentences
+    pub quotes: QuoteMode,
+    /// Keep trailing reference sections ("References", "Daftar Pustaka") in scoring
+    pub include_references: bool,
+    /// Sentences of the assignment template; submission sentences matching
+    /// one of them are removed before analysis
+    pub template: Vec<String>,
+    /// Minimum term-frequency cosine for a sentence to count as copied from the template
+    pub template_threshold: f32,
+    /// Suppress sentences whose normalized text appears in more than this
+    /// fraction of the documents (and in at least three); `None` disables it
+    pub boilerplate_fraction: Option<f32>,
+    /// How sentence similarities are aggregated into the global score
+    pub aggregation: AggregationStrategy,
+    /// Restrict matches to a one-to-one alignment of each document pair
+    pub alignment: AlignmentMode,
+    /// Return at most this many matches, selected without sorting all of them; `None` returns all
+    pub limit: Option<usize>,
+    /// Skip this many matches (after `cursor`) before the returned page
+    pub offset: usize,
+    /// Return only matches after this position in the result order
+    pub cursor: Option<MatchCursor>,
+    /// Keep at most this many best matches per document pair
+    pub per_document_pair_limit: Option<usize>,
+    /// Also compute NxN document similarity matrices
+    pub similarity_matrices: bool,
+    /// Group documents by their global similarity with this method
+    pub clustering: Option<ClusterMethod>,
+    /// Minimum global similarity for documents to share a cluster
+    pub cluster_threshold: f32,
+}
+
+impl SentenceAnalysisOptions {
+    /// Whether only a page of the matches is requested
+    fn pages(&self) -> bool {
+        self.limit.is_some() || self.offset > 0 || self.cursor.is_some()
+    }
+}
+
+impl Default for SentenceAnalysisOptions {
+    fn default() -> Self {
+        Self {
+            threshold: 0.70,
+            min_tokens: 3,
+            min_chars: 0,
+            quotes: QuoteMode::Off,
+            include_references: false,
+            template: Vec::new(),
+            template_threshold: 0.80,
+            boilerplate_fraction: None,
+            aggregation: AggregationStrategy::Mean,
+            alignment: AlignmentMode::Off,
+            limit: None,
+            offset: 0,
+            cursor: None,
+            per_document_pair_limit: None,
+            similarity_matrices: false,
+            clustering: None,
+            cluster_threshold: 0.50,
+        }
+    }
+}
+
+/// Result of sentence-level analysis
+#[derive(Debug, Clone, Default)]
+pub struct SentenceAnalysis {
+    /// Sentence pairs at or above the threshold, sorted by similarity
+    /// descending (the requested page only; empty for a streamed analysis)
+    pub matches: Vec<SentenceMatch>,
+    /// Number of matches available for paging
+    pub total_matches: usize,
+    /// Position of the last returned match when more matches follow the page
+    pub next_cursor: Option<MatchCursor>,
+    /// Document-level similarity for every document pair
+    pub global_similarity: Vec<GlobalSimilarity>,
+    /// Number of sentences excluded from matching as too short
+    pub filtered_sentences: usize,
+    /// Number of quoted or cited sentences (0 when detection is off)
+    pub quoted_sentences: usize,
+    /// Number of sentences in reference sections excluded from scoring
+    pub reference_sentences: usize,
+    /// Per-document sentence statistics, in input order
+    pub documents: Vec<DocumentSummary>,
+    /// Sentences suppressed as common boilerplate
+    pub common_boilerplate: Vec<BoilerplateSentence>,
+    /// Whole-document TF-IDF cosine of every document pair (with `similarity_matrices`)
+    pub document_matrix: Option<SimilarityMatrix>,
+    /// Global score of every document pair, aggregated with `aggregation` (with `similarity_matrices`)
+    pub sentence_matrix: Option<SimilarityMatrix>,
+    /// Groups of similar documents (with `clustering`)
+    pub clustering: Option<DocumentClustering>,
+}
+
+impl SentenceAnalysis {
+    /// Build the API response for an analysis of `documents`
+    pub fn into_response(
+        self,
+        documents: &[SentenceDocument],
+        threshold: f32,
+        processing_time_ms: u64,
+        warnings: Vec<String>,
+    ) -> SentenceAnalysisResponse {
+        let total_sentences: usize = documents.iter().map(|d| d.sentences.len()).sum();
+
+        let metadata = AnalysisMetadata::new(documents.len(), total_sentences, processing_time_ms, threshold)
+            .with_filtered_sentences(self.filtered_sentences)
+            .with_quoted_sentences(self.quoted_sentences)
+            .with_reference_sentences(self.reference_sentences)
+            .with_total_matches(self.total_matches)
+            .with_warnings(warnings);
+
+        SentenceAnalysisResponse::new(metadata, self.matches, self.global_similarity)
+            .with_next_cursor(self.next_cursor.map(|cursor| cursor.encode()))
+            .with_documents(self.documents)
+            .with_common_boilerplate(self.common_boilerplate)
+            .with_similarity_matrices(self.document_matrix, self.sentence_matrix)
+            .with_clustering(self.clustering)
+    }
 }
 
 /// Analyze sentence-level similarity across multiple documents
+///
+/// Every sentence takes part in matching; use
+/// [`analyze_sentence_similarity_with_options`] to filter out fragments.
 pub fn analyze_sentence_similarity(
     documents: &[SentenceDocument],
     threshold: f32,
 ) -> (Vec<SentenceMatch>, Vec<GlobalSimilarity>) {
-    // Step 1: Flatten all sentences with their document context
-    let all_sentences: Vec<(usize, usize, String)> = documents
+    let options = SentenceAnalysisOptions {
+        threshold,
+        min_tokens: 0,
+        min_chars: 0,
+        quotes: QuoteMode::Off,
+        include_references: true,
+        ..SentenceAnalysisOptions::default()
+    };
+    let analysis = analyze_sentence_similarity_with_options(documents, &options);
+
+    (analysis.matches, analysis.global_similarity)
+}
+
+/// Analyze sentence-level similarity across multiple documents
+///
+/// Sentences shorter than `options.min_tokens` tokens or `options.min_chars`
+/// characters (headings, "Table 1.", single words) keep their index in the
+/// document but are excluded from matching and from the global similarity.
+///
+/// With `QuoteMode::Mark`, matches involving a sentence inside quotation
+/// marks or containing an in-text citation are flagged `quoted`/`cited`;
+/// with `QuoteMode::Exclude` such sentences are excluded like fragments.
+///
+/// Unless `options.include_references` is set, each document's trailing
+/// reference section (from its heading on) is excluded from scoring.
+/// Sentences matching a sentence of `options.template` are removed as well
+/// and counted per document. With `options.boilerplate_fraction`, sentences
+/// shared by a large fraction of the documents are reported as common
+/// boilerplate instead of as matches. With `options.alignment`, each
+/// sentence is matched at most once per document pair, and with
+/// `options.per_document_pair_limit` only the best matches of each pair are
+/// kept.
+///
+/// `options.limit`, `options.offset` and `options.cursor` select a page of
+/// the matches; the page is taken from a bounded heap of `offset + limit`
+/// entries instead of a full sort.
+pub fn analyze_sentence_similarity_with_options(
+    documents: &[SentenceDocument],
+    options: &SentenceAnalysisOptions,
+) -> SentenceAnalysis {
+    let Ok(analysis) = analyze_sentence_similarity_within_budget(documents, options, usize::MAX) else {
+        unreachable!("an analysis cannot exceed usize::MAX bytes");
+    };
+    analysis
+}
+
+/// `analyze_sentence_similarity_with_options` within a memory budget of
+/// `budget` bytes
+///
+/// The analysis is refused once the sentences are tokenized, before any
+/// pair is compared, when the memory estimate exceeds the budget. It is
+/// stopped as soon as the estimate plus the matches kept so far do; each
+/// match is charged as it is kept, so with a low threshold and no `limit`
+/// the analysis stops before the matches outgrow the budget.
+pub fn analyze_sentence_similarity_within_budget(
+    documents: &[SentenceDocument],
+    options: &SentenceAnalysisOptions,
+    budget: usize,
+) -> Result<SentenceAnalysis, MemoryBudgetExceeded> {
+    let (sentences, estimate) = tokenize_within_budget(documents, options, budget)?;
+    let (analysis, collector) = collect_analysis(documents, sentences, options, budget - estimate.total(), || false);
+    let (matches, next_cursor) = collector.into_page(budget, &estimate)?;
+
+    Ok(SentenceAnalysis {
+        matches,
+        next_cursor,
+        ..analysis
+    })
+}
+
+/// Run the pipeline, keeping the requested page of matches in a collector
+/// that stops the analysis once its matches exceed `match_budget` bytes
+fn collect_analysis<'a, C>(
+    documents: &'a [SentenceDocument],
+    sentences: Vec<TokenizedSentence<'a>>,
+    options: &SentenceAnalysisOptions,
+    match_budget: usize,
+    cancelled: C,
+) -> (SentenceAnalysis, MatchCollector)
+where
+    C: Fn() -> bool + Sync,
+{
+    let collector = Mutex::new(MatchCollector::new(options, match_budget));
+    let over_budget = AtomicBool::new(false);
+    let analysis = run_analysis(
+        documents,
+        sentences,
+        options,
+        |keys, builder| {
+            let mut collector = collector.lock().unwrap();
+            collector.add(keys, builder);
+            if collector.over_budget() {
+                over_budget.store(true, Ordering::Relaxed);
+            }
+        },
+        || over_budget.load(Ordering::Relaxed) || cancelled(),
+    );
+
+    (analysis, collector.into_inner().unwrap())
+}
+
+/// Analyze sentence-level similarity, handing each match to `on_match` as
+/// soon as it is found
+///
+/// Matches arrive unsorted, in batches of at most `MATCH_BATCH` per document
+/// pair, and are never held all at once; the similarity block of a document
+/// pair is dropped once the pair is done. When a page is requested (`limit`,
+/// `offset` or `cursor`), only that page is kept and it is handed over best
+/// first after all pairs are compared. The returned analysis has no
+/// `matches`.
+pub fn analyze_sentence_similarity_streaming<F>(
+    documents: &[SentenceDocument],
+    options: &SentenceAnalysisOptions,
+    on_match: F,
+) -> SentenceAnalysis
+where
+    F: Fn(SentenceMatch) + Sync,
+{
+    analyze_sentence_similarity_streaming_until(documents, options, on_match, || false)
+}
+
+/// `analyze_sentence_similarity_streaming` that stops early once
+/// `cancelled` returns true (e.g. the client went away)
+///
+/// `cancelled` is checked before each document pair is compared and after
+/// each batch of matches; the rest of the analysis is skipped, so the
+/// returned analysis is then incomplete.
+pub fn analyze_sentence_similarity_streaming_until<F, C>(
+    documents: &[SentenceDocument],
+    options: &SentenceAnalysisOptions,
+    on_match: F,
+    cancelled: C,
+) -> SentenceAnalysis
+where
+    F: Fn(SentenceMatch) + Sync,
+    C: Fn() -> bool + Sync,
+{
+    let result = analyze_sentence_similarity_streaming_within_budget(documents, options, usize::MAX, on_match, cancelled);
+    let Ok(analysis) = result else {
+        unreachable!("an analysis cannot exceed usize::MAX bytes");
+    };
+    analysis
+}
+
+/// `analyze_sentence_similarity_streaming_until` within a memory budget of
+/// `budget` bytes
+///
+/// The budget is checked as in `analyze_sentence_similarity_within_budget`;
+/// streamed matches are not kept, so only a requested page counts towards
+/// it. When the estimate exceeds the budget, the error is returned before
+/// any match is handed to `on_match`.
+pub fn analyze_sentence_similarity_streaming_within_budget<F, C>(
+    documents: &[SentenceDocument],
+    options: &SentenceAnalysisOptions,
+    budget: usize,
+    on_match: F,
+    cancelled: C,
+) -> Result<SentenceAnalysis, MemoryBudgetExceeded>
+where
+    F: Fn(SentenceMatch) + Sync,
+    C: Fn() -> bool + Sync,
+{
+    let (sentences, estimate) = tokenize_within_budget(documents, options, budget)?;
+    if !options.pages() {
+        return Ok(run_analysis(
+            documents,
+            sentences,
+            options,
+            |keys, builder| keys.into_iter().for_each(|key| on_match(builder.build(key))),
+            cancelled,
+        ));
+    }
+
+    let (analysis, collector) = collect_analysis(documents, sentences, options, budget - estimate.total(), &cancelled);
+    if cancelled() {
+        return Ok(analysis);
+    }
+    let (matches, next_cursor) = collector.into_page(budget, &estimate)?;
+    matches.into_iter().for_each(on_match);
+
+    Ok(SentenceAnalysis { next_cursor, ..analysis })
+}
+
+/// A match with its position in the result order
+struct RankedMatch {
+    key: MatchCursor,
+    item: SentenceMatch,
+}
+
+impl RankedMatch {
+    /// Bytes held by the match, including its copied strings
+    fn memory(&self) -> usize {
+        let item = &self.item;
+        size_of::<Self>()
+            + item.source_doc.len()
+            + item.source_sentence.len()
+            + item.target_doc.len()
+            + item.target_sentence.len()
+    }
+}
+
+impl PartialEq for RankedMatch {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for RankedMatch {}
+
+impl PartialOrd for RankedMatch {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RankedMatch {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+/// Collects the requested page of matches
+struct MatchCollector {
+    after: Option<MatchCursor>,
+    offset: usize,
+    limit: Option<usize>,
+    store: MatchStore,
+    /// Bytes held by the kept matches
+    bytes: usize,
+    /// Bytes the kept matches may hold
+    budget: usize,
+}
+
+enum MatchStore {
+    /// Every match after the cursor
+    All(Vec<RankedMatch>),
+    /// Only the first `offset + limit + 1` matches after the cursor; the
+    /// extra one tells whether another page follows
+    Top(TopK<RankedMatch>),
+}
+
+impl MatchCollector {
+    fn new(options: &SentenceAnalysisOptions, budget: usize) -> Self {
+        let store = match options.limit {
+            Some(limit) => MatchStore::Top(TopK::new(options.offset.saturating_add(limit).saturating_add(1))),
+            None => MatchStore::All(Vec::new()),
+        };
+
+        Self {
+            after: options.cursor,
+            offset: options.offset,
+            limit: options.limit,
+            store,
+            bytes: 0,
+            budget,
+        }
+    }
+
+    fn over_budget(&self) -> bool {
+        self.bytes > self.budget
+    }
+
+    /// Keep the matches of `keys` that belong to the page, building only
+    /// those that rank high enough to be kept
+    ///
+    /// Each match is charged as it is kept; once the kept matches exceed the
+    /// budget, the rest of `keys` is ignored.
+    fn add(&mut self, keys: Vec<MatchCursor>, builder: &MatchBuilder) {
+        let after = self.after;
+        let keys = keys.into_iter().filter(|key| after.is_none_or(|cursor| *key > cursor));
+
+        for key in keys {
+            if self.over_budget() {
+                return;
+            }
+            if let MatchStore::Top(top) = &self.store {
+                if top.worst_kept().is_some_and(|worst| key >= worst.key) {
+                    continue;
+                }
+            }
+
+            let ranked = RankedMatch {
+                key,
+                item: builder.build(key),
+            };
+            self.bytes += ranked.memory();
+            let dropped = match &mut self.store {
+                MatchStore::All(all) => {
+                    all.push(ranked);
+                    None
+                }
+                MatchStore::Top(top) => top.push(ranked),
+            };
+            if let Some(dropped) = dropped {
+                self.bytes -= dropped.memory();
+            }
+        }
+    }
+
+    /// The requested page, best first, and the cursor of the next page, or
+    /// the error of an analysis whose kept matches exceeded the budget left
+    /// by `estimate` out of `budget`
+    fn into_page(
+        self,
+        budget: usize,
+        estimate: &MemoryEstimate,
+    ) -> Result<(Vec<SentenceMatch>, Option<MatchCursor>), MemoryBudgetExceeded> {
+        if self.over_budget() {
+            return Err(MemoryBudgetExceeded {
+                estimated: estimate.total() + self.bytes,
+                budget,
+            });
+        }
+
+        let sorted = match self.store {
+            MatchStore::All(mut all) => {
+                all.sort_unstable();
+                all
+            }
+            MatchStore::Top(top) => top.into_sorted_vec(),
+        };
+
+        let mut page: Vec<RankedMatch> = sorted.into_iter().skip(self.offset).collect();
+        let next_cursor = match self.limit {
+            Some(limit) if page.len() > limit => {
+                page.truncate(limit);
+                page.last().map(|ranked| ranked.key)
+            }
+            _ => None,
+        };
+
+        Ok((page.into_iter().map(|ranked| ranked.item).collect(), next_cursor))
+    }
+}
+
+/// Run the pipeline, handing the match keys of each document pair to `emit`
+/// in batches, with the builder of their matches
+///
+/// Document pairs and batches not yet handed over when `cancelled` returns
+/// true are skipped. `processed_sentences` are the `tokenize_documents` of
+/// `documents`.
+fn run_analysis<'a, F, C>(
+    documents: &'a [SentenceDocument],
+    mut processed_sentences: Vec<TokenizedSentence<'a>>,
+    options: &SentenceAnalysisOptions,
+    emit: F,
+    cancelled: C,
+) -> SentenceAnalysis
+where
+    F: Fn(Vec<MatchCursor>, &MatchBuilder) + Sync,
+    C: Fn() -> bool + Sync,
+{
+    if processed_sentences.is_empty() {
+        return SentenceAnalysis {
+            documents: summarize_documents(documents, &vec![0; documents.len()]),
+            ..SentenceAnalysis::default()
+        };
+    }
+
+    // Step 1b: Detect quoted and cited sentences
+    let attributions = detect_attributions(documents, options.quotes);
+    let quoted_count = attributions.iter().flatten().filter(|a| a.any()).count();
+
+    // Step 1c: Locate trailing reference sections
+    let reference_starts: Vec<usize> = documents
         .iter()
-        .enumerate()
-        .flat_map(|(doc_idx, doc)| {
-            doc.sentences
-                .iter()
-                .enumerate()
-                .map(move |(sent_idx, sentence)| (doc_idx, sent_idx, sentence.clone()))
+        .map(|doc| {
+            let start = if options.include_references {
+                None
+            } else {
+                find_reference_section(&doc.sentences)
+            };
+            start.unwrap_or(doc.sentences.len())
         })
         .collect();
 
-    if all_sentences.is_empty() {
-        return (vec![], vec![]);
-    }
+    // Step 2: The filters below drop tokenized sentences in place
 
-    // Step 2: Process each sentence (normalize + tokenize)
-    let processed_sentences: Vec<(usize, usize, String, Vec<String>)> = all_sentences
+    // Step 2a: Drop sentences copied from the assignment template
+    let mut template_vocabulary = Vocabulary::new();
+    let template_tfs: Vec<SparseVector> = options
+        .template
+        .iter()
+        .map(|sentence| {
+            let ids = template_vocabulary.intern_all(&tokenize(&normalize_text(sentence)));
+            SparseVector::term_frequencies(&ids).normalized()
+        })
+        .filter(|tf| !tf.is_empty())
+        .collect();
+    let from_template: Vec<bool> = processed_sentences
         .par_iter()
-        .map(|(doc_idx, sent_idx, text)| {
-            let normalized = normalize_text(text);
-            let tokens = tokenize(&normalized);
-            (*doc_idx, *sent_idx, text.clone(), tokens)
+        .map(|(_, _, _, tokens)| {
+            matches_template(tokens, &template_vocabulary, &template_tfs, options.template_threshold)
         })
         .collect();
+    let mut template_counts = vec![0usize; documents.len()];
+    let mut from_template = from_template.into_iter();
+    processed_sentences.retain(|(doc_idx, _, _, _)| {
+        // `retain` visits the sentences in order, in step with `from_template`
+        let copied = from_template.next().unwrap_or(false);
+        if copied {
+            template_counts[*doc_idx] += 1;
+        }
+        !copied
+    });
+
+    // Step 2b: Drop sentences shared by a large fraction of the documents
+    let boilerplate = options
+        .boilerplate_fraction
+        .map(|fraction| find_boilerplate(documents, fraction))
+        .unwrap_or_default();
+    if !boilerplate.is_empty() {
+        processed_sentences.retain(|(_, _, text, _)| !boilerplate.contains_key(&sentence_hash(text)));
+    }
+
+    // Step 2c: Drop fragments too short to match meaningfully
+    let total = processed_sentences.len();
+    processed_sentences.retain(|(_, _, text, tokens)| {
+        tokens.len() >= options.min_tokens && text.trim().chars().count() >= options.min_chars
+    });
+    let filtered_sentences = total - processed_sentences.len();
+
+    // Step 2d: Drop quoted and cited sentences when requested
+    if options.quotes == QuoteMode::Exclude {
+        processed_sentences.retain(|(doc_idx, sent_idx, _, _)| !attributions[*doc_idx][*sent_idx].any());
+    }
+
+    // Step 2e: Drop reference sections, counting only the sentences that
+    // survived the filters above
+    let remaining = processed_sentences.len();
+    processed_sentences.retain(|(doc_idx, sent_idx, _, _)| *sent_idx < reference_starts[*doc_idx]);
+    let reference_count = remaining - processed_sentences.len();
+
+    // Step 3: Intern terms and compute TF for each sentence
+    let mut vocabulary = Vocabulary::new();
+    let sentence_ids: Vec<(usize, usize, Vec<u32>)> = processed_sentences
+        .into_iter()
+        .map(|(doc_idx, sent_idx, _text, tokens)| (doc_idx, sent_idx, vocabulary.intern_all(&tokens)))
+        .collect();
+
+    // Step 3b: Collect whole-document term ids for document-level aggregation and matrices
+    let document_ids: Vec<Vec<u32>> = if options.aggregation == AggregationStrategy::Document
+        || options.similarity_matrices
+    {
+        sentence_ids
+            .iter()
+            .fold(vec![Vec::new(); documents.len()], |mut acc, (doc_idx, _, ids)| {
+                acc[*doc_idx].extend_from_slice(ids);
+                acc
+            })
+    } else {
+        Vec::new()
+    };
 
-    // Step 3: Compute TF for each sentence
-    let sentence_tfs: Vec<(usize, usize, String, HashMap<String, f32>)> = processed_sentences
+    let sentence_tfs: Vec<(usize, usize, usize, SparseVector)> = sentence_ids
         .into_par_iter()
-        .map(|(doc_idx, sent_idx, text, tokens)| {
-            let tf = compute_tf(&tokens);
-            (doc_idx, sent_idx, text, tf)
+        .map(|(doc_idx, sent_idx, ids)| {
+            let tf = SparseVector::term_frequencies(&ids);
+            (doc_idx, sent_idx, ids.len(), tf)
         })
         .collect();
 
     // Step 4: Compute global IDF from all sentences
-    let tfs_only: Vec<HashMap<String, f32>> = sentence_tfs
-        .iter()
-        .map(|(_, _, _, tf)| tf.clone())
-        .collect();
-    let global_idf = compute_idf(&tfs_only);
+    let global_idf = compute_sparse_idf(sentence_tfs.iter().map(|(_, _, _, tf)| tf), vocabulary.len());
 
-    // Step 5: Compute TF-IDF vectors for each sentence
+    // Step 5: Compute TF-IDF vectors for each sentence, normalized once so
+    // every comparison is a plain dot product
     let sentence_vectors: Vec<SentenceVector> = sentence_tfs
         .into_par_iter()
-        .map(|(doc_idx, sent_idx, _text, tf)| {
-            let vector = compute_tfidf_vector(&tf, &global_idf);
+        .map(|(doc_idx, sent_idx, token_count, tf)| {
+            let vector = tf.weighted(&global_idf).normalized();
             SentenceVector {
                 doc_index: doc_idx,
                 sentence_index: sent_idx,
+                token_count,
                 vector,
             }
         })
         .collect();
 
-    // Step 6: Compute pairwise similarities (cross-document only)
-    let matches = compute_sentence_matches(&sentence_vectors, documents, threshold);
+    // Step 6: Compare every document pair: similarity block, matches and
+    // global similarity. Each block is dropped as soon as its pair is done
+    let document_vectors = document_vectors(&document_ids, vocabulary.len());
+    let builder = MatchBuilder {
+        documents,
+        attributions: &attributions,
+    };
+    let total_matches = AtomicUsize::new(0);
+    let (pair_indices, global_similarity): (Vec<(usize, usize)>, Vec<GlobalSimilarity>) =
+        document_pairs(&sentence_vectors, documents.len())
+            .into_par_iter()
+            .filter(|_| !cancelled())
+            .map(|pair| {
+                let block = similarity_block(&pair.rows, &pair.cols);
+
+                // Step 7: Matches of this pair (cross-document only)
+                pair_matches(&pair, &block, options, &cancelled, |keys| {
+                    total_matches.fetch_add(keys.len(), Ordering::Relaxed);
+                    emit(keys, &builder);
+                });
+
+                // Step 8: Global similarity of this pair
+                let global = pair_global_similarity(&pair, &block, &document_vectors, documents, options);
+                ((pair.doc_a, pair.doc_b), global)
+            })
+            .unzip();
+
+    // Step 9: Arrange document-pair scores as NxN matrices
+    let index: Vec<String> = documents.iter().map(|d| d.filename.clone()).collect();
+    let sentence_matrix = (options.similarity_matrices || options.clustering.is_some()).then(|| {
+        let scores: HashMap<(usize, usize), f32> = pair_indices
+            .iter()
+            .copied()
+            .zip(global_similarity.iter().map(|global| global.score))
+            .collect();
+        let matrix = symmetric_matrix(documents.len(), |i, j| scores.get(&(i, j)).copied().unwrap_or(0.0));
+        SimilarityMatrix::new(matrix, index.clone())
+    });
+    let document_matrix = options.similarity_matrices.then(|| {
+        let matrix = symmetric_matrix(documents.len(), |i, j| {
+            document_vectors[i].dot(&document_vectors[j])
+        });
+        SimilarityMatrix::new(matrix, index)
+    });
+
+    // Step 10: Group documents from the sentence-aggregation matrix
+    let clustering = options
+        .clustering
+        .zip(sentence_matrix.as_ref())
+        .map(|(method, matrix)| cluster_documents(matrix, method, options.cluster_threshold));
+    let sentence_matrix = sentence_matrix.filter(|_| options.similarity_matrices);
 
-    // Step 7: Compute global document similarities
-    let global_similarities = compute_global_similarities(&sentence_vectors, documents);
+    // Sort by score descending, NaN last, ties by document indices
+    let mut ranked: Vec<((usize, usize), GlobalSimilarity)> =
+        pair_indices.into_iter().zip(global_similarity).collect();
+    ranked.sort_by(|(pair_a, a), (pair_b, b)| descending_similarity(a.score, b.score).then(pair_a.cmp(pair_b)));
+    let global_similarity: Vec<GlobalSimilarity> = ranked.into_iter().map(|(_, global)| global).collect();
 
-    (matches, global_similarities)
+    SentenceAnalysis {
+        matches: Vec::new(),
+        total_matches: total_matches.into_inner(),
+        next_cursor: None,
+        global_similarity,
+        filtered_sentences,
+        quoted_sentences: quoted_count,
+        reference_sentences: reference_count,
+        documents: summarize_documents(documents, &template_counts),
+        common_boilerplate: report_boilerplate(boilerplate),
+        document_matrix,
+        sentence_matrix,
+        clustering,
+    }
 }
 
-fn compute_sentence_matches(
-    vectors: &[SentenceVector],
-    documents: &[SentenceDocument],
-    threshold: f32,
-) -> Vec<SentenceMatch> {
-    // Generate all pairs, filter by threshold, and sort by similarity descending
-    let mut matches: Vec<SentenceMatch> = vectors
+/// A sentence with its document index, sentence index, text and normalized tokens
+type TokenizedSentence<'a> = (usize, usize, &'a str, Vec<String>);
+
+/// Normalize and tokenize every sentence of every document
+fn tokenize_documents(documents: &[SentenceDocument]) -> Vec<TokenizedSentence<'_>> {
+    // Flatten all sentences with their document context, borrowing the text
+    let all_sentences: Vec<(usize, usize, &str)> = documents
         .iter()
         .enumerate()
-        .flat_map(|(i, vec_a)| {
-            vectors.iter().skip(i + 1).filter_map(move |vec_b| {
-                // Only compare sentences from different documents
-                if vec_a.doc_index == vec_b.doc_index {
-                    return None;
-                }
+        .flat_map(|(doc_idx, doc)| {
+            doc.sentences
+                .iter()
+                .enumerate()
+                .map(move |(sent_idx, sentence)| (doc_idx, sent_idx, sentence.as_str()))
+        })
+        .collect();
 
-                let similarity = compute_cosine_similarity(&vec_a.vector, &vec_b.vector);
-
-                if similarity >= threshold {
-                    let source_doc = documents[vec_a.doc_index].filename.clone();
-                    let target_doc = documents[vec_b.doc_index].filename.clone();
-                    
-                    // Get actual sentence text
-                    let source_sentence = documents[vec_a.doc_index].sentences[vec_a.sentence_index].clone();
-                    let target_sentence = documents[vec_b.doc_index].sentences[vec_b.sentence_index].clone();
-
-                    Some(SentenceMatch::new(
-                        source_doc,
-                        vec_a.sentence_index,
-                        source_sentence,
-                        target_doc,
-                        vec_b.sentence_index,
-                        target_sentence,
-                        similarity,
-                    ))
-                } else {
-                    None
-                }
+    all_sentences
+        .into_par_iter()
+        .map(|(doc_idx, sent_idx, text)| {
+            let normalized = normalize_text(text);
+            let tokens = tokenize(&normalized);
+            (doc_idx, sent_idx, text, tokens)
+        })
+        .collect()
+}
+
+/// Tokenize the documents and check the memory estimate of analyzing them,
+/// counting their tokens rather than tokenizing them twice
+fn tokenize_within_budget<'a>(
+    documents: &'a [SentenceDocument],
+    options: &SentenceAnalysisOptions,
+    budget: usize,
+) -> Result<(Vec<TokenizedSentence<'a>>, MemoryEstimate), MemoryBudgetExceeded> {
+    let sentences = tokenize_documents(documents);
+    let tokens = sentences.iter().map(|(_, _, _, tokens)| tokens.len()).sum();
+    let estimate = estimate_memory_with_tokens(documents, tokens, options);
+    estimate.check(budget)?;
+
+    Ok((sentences, estimate))
+}
+
+/// Hash of a sentence's normalized text
+fn sentence_hash(sentence: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    normalize_text(sentence).hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Sentences appearing in more than `fraction` of the documents, by normalized
+/// hash, with their first occurrence and the number of documents containing them
+fn find_boilerplate(documents: &[SentenceDocument], fraction: f32) -> HashMap<u64, (String, usize)> {
+    let mut first_occurrence: HashMap<u64, &String> = HashMap::new();
+    let mut document_counts: HashMap<u64, usize> = HashMap::new();
+
+    for doc in documents {
+        let hashes: HashSet<u64> = doc
+            .sentences
+            .iter()
+            .filter(|sentence| !normalize_text(sentence).is_empty())
+            .map(|sentence| {
+                let hash = sentence_hash(sentence);
+                first_occurrence.entry(hash).or_insert(sentence);
+                hash
+            })
+            .collect();
+
+        for hash in hashes {
+            *document_counts.entry(hash).or_insert(0) += 1;
+        }
+    }
+
+    let min_documents = ((fraction * documents.len() as f32).floor() as usize + 1)
+        .max(MIN_BOILERPLATE_DOCUMENTS);
+
+    document_counts
+        .into_iter()
+        .filter(|(_, count)| *count >= min_documents)
+        .map(|(hash, count)| (hash, (first_occurrence[&hash].clone(), count)))
+        .collect()
+}
+
+/// Boilerplate sentences sorted by document count (descending), then text
+fn report_boilerplate(boilerplate: HashMap<u64, (String, usize)>) -> Vec<BoilerplateSentence> {
+    let mut report: Vec<BoilerplateSentence> = boilerplate
+        .into_values()
+        .map(|(sentence, documents)| BoilerplateSentence::new(sentence, documents))
+        .collect();
+    report.sort_by(|a, b| b.documents.cmp(&a.documents).then_with(|| a.sentence.cmp(&b.sentence)));
+    report
+}
+
+/// Whether a sentence matches one of the template sentences
+fn matches_template(
+    tokens: &[String],
+    vocabulary: &Vocabulary,
+    template_tfs: &[SparseVector],
+    threshold: f32,
+) -> bool {
+    if template_tfs.is_empty() || tokens.is_empty() {
+        return false;
+    }
+
+    // Terms missing from the template only add to the sentence's norm; give
+    // them ids past the template vocabulary
+    let mut unknown: HashMap<&str, u32> = HashMap::new();
+    let ids: Vec<u32> = tokens
+        .iter()
+        .map(|token| {
+            vocabulary.id(token).unwrap_or_else(|| {
+                let next = (vocabulary.len() + unknown.len()) as u32;
+                *unknown.entry(token.as_str()).or_insert(next)
             })
         })
         .collect();
 
-    // Sort by similarity descending (must use mut here as sort_by requires &mut self)
-    matches.sort_by(|a, b| b.similarity.partial_cmp(&a.similarity).unwrap());
+    let tf = SparseVector::term_frequencies(&ids).normalized();
+    template_tfs
+        .iter()
+        .any(|template_tf| tf.dot(template_tf) >= threshold)
+}
+
+fn summarize_documents(documents: &[SentenceDocument], template_counts: &[usize]) -> Vec<DocumentSummary> {
+    documents
+        .iter()
+        .zip(template_counts)
+        .map(|(doc, &template_sentences)| {
+            DocumentSummary::new(doc.filename.clone(), doc.sentences.len())
+                .with_template_sentences(template_sentences)
+        })
+        .collect()
+}
+
+/// Quotation and citation flags for every sentence of every document
+fn detect_attributions(documents: &[SentenceDocument], mode: QuoteMode) -> Vec<Vec<Attribution>> {
+    documents
+        .iter()
+        .map(|doc| {
+            if mode == QuoteMode::Off {
+                return vec![Attribution::default(); doc.sentences.len()];
+            }
 
-    matches
+            quoted_sentences(&doc.sentences)
+                .into_iter()
+                .zip(&doc.sentences)
+                .map(|(quoted, sentence)| Attribution {
+                    quoted,
+                    cited: has_citation(sentence),
+                })
+                .collect()
+        })
+        .collect()
 }
 
-/// Compute global similarity between document pairs
-fn compute_global_similarities(
-    vectors: &[SentenceVector],
-    documents: &[SentenceDocument],
-) -> Vec<GlobalSimilarity> {
+/// Hand the keys of the matches between the sentences of one document pair
+/// to `emit`, in batches of at most `MATCH_BATCH`, best first when
+/// `options.per_document_pair_limit` applies
+///
+/// Only keys are collected; the matched sentences are copied once a match
+/// is kept. No further batch is made once `cancelled` returns true.
+fn pair_matches<C, E>(
+    pair: &DocumentPair,
+    block: &SimilarityBlock,
+    options: &SentenceAnalysisOptions,
+    cancelled: C,
+    mut emit: E,
+) where
+    C: Fn() -> bool,
+    E: FnMut(Vec<MatchCursor>),
+{
+    let threshold = options.threshold;
+    let pairs: Box<dyn Iterator<Item = (usize, usize)>> = match options.alignment {
+        AlignmentMode::Off => Box::new(
+            (0..block.rows())
+                .flat_map(|row| (0..block.cols()).map(move |col| (row, col)))
+                .filter(move |&(row, col)| block.get(row, col) >= threshold),
+        ),
+        AlignmentMode::Bipartite => Box::new(bipartite_alignment(block, threshold).into_iter()),
+        AlignmentMode::Monotonic => Box::new(monotonic_alignment(block, threshold).into_iter()),
+    };
+
+    let keys = pairs.map(|(row, col)| {
+        let (vec_a, vec_b) = (pair.rows[row], pair.cols[col]);
+        MatchCursor {
+            similarity: block.get(row, col),
+            source_doc: vec_a.doc_index,
+            source_sentence_index: vec_a.sentence_index,
+            target_doc: vec_b.doc_index,
+            target_sentence_index: vec_b.sentence_index,
+        }
+    });
+
+    let keys: Box<dyn Iterator<Item = MatchCursor>> = match options.per_document_pair_limit {
+        Some(limit) => {
+            let mut top = TopK::new(limit);
+            for key in keys {
+                top.push(key);
+            }
+            Box::new(top.into_sorted_vec().into_iter())
+        }
+        None => Box::new(keys),
+    };
+
+    let mut batch = Vec::new();
+    for key in keys {
+        batch.push(key);
+        if batch.len() == MATCH_BATCH {
+            emit(std::mem::take(&mut batch));
+            if cancelled() {
+                return;
+            }
+        }
+    }
+    if !batch.is_empty() {
+        emit(batch);
+    }
+}
+
+/// Builds the match named by a key from the documents
+struct MatchBuilder<'a> {
+    documents: &'a [SentenceDocument],
+    attributions: &'a [Vec<Attribution>],
+}
+
+impl MatchBuilder<'_> {
+    fn build(&self, key: MatchCursor) -> SentenceMatch {
+        let source = &self.documents[key.source_doc];
+        let target = &self.documents[key.target_doc];
+        let source_attribution = self.attributions[key.source_doc][key.source_sentence_index];
+        let target_attribution = self.attributions[key.target_doc][key.target_sentence_index];
+
+        SentenceMatch::new(
+            source.filename.clone(),
+            key.source_sentence_index,
+            source.sentences[key.source_sentence_index].clone(),
+            target.filename.clone(),
+            key.target_sentence_index,
+            target.sentences[key.target_sentence_index].clone(),
+            key.similarity,
+        )
+        .with_attribution(
+            source_attribution.quoted || target_attribution.quoted,
+            source_attribution.cited || target_attribution.cited,
+        )
+        .with_doc_indices(key.source_doc, key.target_doc)
+    }
+}
+
+/// Scored sentences of two documents
+struct DocumentPair<'a> {
+    doc_a: usize,
+    doc_b: usize,
+    rows: Vec<&'a SentenceVector>,
+    cols: Vec<&'a SentenceVector>,
+}
+
+/// Every document pair that has scored sentences in both documents
+fn document_pairs(vectors: &[SentenceVector], document_count: usize) -> Vec<DocumentPair<'_>> {
     // Group vectors by document using fold (more functional than mut + for loop)
-    let doc_vectors: HashMap<usize, Vec<&SentenceVector>> = vectors
+    let doc_vectors: Vec<Vec<&SentenceVector>> = vectors
         .iter()
-        .fold(HashMap::new(), |mut acc, vector| {
-            acc.entry(vector.doc_index)
-                .or_insert_with(Vec::new)
-                .push(vector);
+        .fold(vec![Vec::new(); document_count], |mut acc, vector| {
+            acc[vector.doc_index].push(vector);
             acc
         });
 
-    let empty_vec: Vec<&SentenceVector> = Vec::new();
-
-    // Collect all document pairs first, then map to compute similarities
-    let doc_pairs: Vec<(usize, usize)> = (0..documents.len())
-        .flat_map(|doc_a_idx| {
-            ((doc_a_idx + 1)..documents.len()).map(move |doc_b_idx| (doc_a_idx, doc_b_idx))
+    (0..document_count)
+        .flat_map(|doc_a_idx| ((doc_a_idx + 1)..document_count).map(move |doc_b_idx| (doc_a_idx, doc_b_idx)))
+        .filter(|(doc_a_idx, doc_b_idx)| !doc_vectors[*doc_a_idx].is_empty() && !doc_vectors[*doc_b_idx].is_empty())
+        .map(|(doc_a, doc_b)| DocumentPair {
+            doc_a,
+            doc_b,
+            rows: doc_vectors[doc_a].clone(),
+            cols: doc_vectors[doc_b].clone(),
         })
-        .collect();
+        .collect()
+}
 
-    // Compute similarity for each pair
-    let mut global_sims: Vec<GlobalSimilarity> = doc_pairs
-        .iter()
-        .filter_map(|(doc_a_idx, doc_b_idx)| {
-            let vecs_a = doc_vectors.get(doc_a_idx).unwrap_or(&empty_vec);
-            let vecs_b = doc_vectors.get(doc_b_idx).unwrap_or(&empty_vec);
+/// Compute the global similarity of a document pair
+///
+/// The score is aggregated with `options.aggregation`. Directional coverage
+/// tells how much of each document is found in the other, so a short text
+/// copied entirely into a long one shows full coverage in one direction.
+fn pair_global_similarity(
+    pair: &DocumentPair,
+    block: &SimilarityBlock,
+    document_vectors: &[SparseVector],
+    documents: &[SentenceDocument],
+    options: &SentenceAnalysisOptions,
+) -> GlobalSimilarity {
+    let threshold = options.threshold;
+    let coverage_a = directional_coverage(&pair.rows, &block.row_max(), threshold);
+    let coverage_b = directional_coverage(&pair.cols, &block.col_max(), threshold);
 
-            if vecs_a.is_empty() || vecs_b.is_empty() {
-                return None;
-            }
+    let score = match options.aggregation {
+        AggregationStrategy::Mean => block.mean(),
+        AggregationStrategy::BestMatch => block.best_match_mean(),
+        AggregationStrategy::Alignment => block.alignment_score(&greedy_alignment(block)),
+        AggregationStrategy::Document => document_vectors[pair.doc_a].dot(&document_vectors[pair.doc_b]),
+    };
 
-            // Compute all cross-document sentence similarities using flat_map
-            let similarities: Vec<f32> = vecs_a
-                .iter()
-                .flat_map(|vec_a| {
-                    vecs_b.iter().map(|vec_b| {
-                        compute_cosine_similarity(&vec_a.vector, &vec_b.vector)
-                    })
-                })
-                .collect();
+    GlobalSimilarity::new(
+        documents[pair.doc_a].filename.clone(),
+        documents[pair.doc_b].filename.clone(),
+        score,
+    )
+    .with_strategy(options.aggregation)
+    .with_coverage(coverage_a, coverage_b)
+    .with_doc_indices(pair.doc_a, pair.doc_b)
+}
 
-            // Average similarity
-            let avg_similarity = similarities.iter().sum::<f32>() / similarities.len() as f32;
+/// Whole-document L2-normalized TF-IDF vectors, with IDF computed over the documents
+fn document_vectors(document_ids: &[Vec<u32>], vocabulary_size: usize) -> Vec<SparseVector> {
+    let tfs: Vec<SparseVector> = document_ids.iter().map(|ids| SparseVector::term_frequencies(ids)).collect();
+    let idf = compute_sparse_idf(&tfs, vocabulary_size);
 
-            Some(GlobalSimilarity::new(
-                documents[*doc_a_idx].filename.clone(),
-                documents[*doc_b_idx].filename.clone(),
-                avg_similarity,
-            ))
+    tfs.iter().map(|tf| tf.weighted(&idf).normalized()).collect()
+}
+
+/// Similarities between every sentence of `rows` and every sentence of `cols`
+fn similarity_block(rows: &[&SentenceVector], cols: &[&SentenceVector]) -> SimilarityBlock {
+    let values = rows
+        .iter()
+        .flat_map(|row| {
+            cols.iter()
+                .map(move |col| row.vector.dot(&col.vector))
         })
         .collect();
 
-    // Sort by score descending (must use mut here as sort_by requires &mut self)
-    global_sims.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+    SimilarityBlock::new(rows.len(), cols.len(), values)
+}
+
+/// How much of a document is found in the other, from the best similarity of each of its sentences
+fn directional_coverage(vectors: &[&SentenceVector], best: &[f32], threshold: f32) -> DirectionalCoverage {
+    let matched = best.iter().filter(|&&similarity| similarity >= threshold).count();
+    let total_tokens: usize = vectors.iter().map(|v| v.token_count).sum();
+    let matched_tokens: usize = vectors
+        .iter()
+        .zip(best)
+        .filter(|(_, &similarity)| similarity >= threshold)
+        .map(|(v, _)| v.token_count)
+        .sum();
 
-    global_sims
+    DirectionalCoverage {
+        sentences: matched as f32 / best.len() as f32,
+        best_match_mean: best.iter().sum::<f32>() / best.len() as f32,
+        tokens: if total_tokens == 0 {
+            0.0
+        } else {
+            matched_tokens as f32 / total_tokens as f32
+        },
+    }
 }
@@ -10,22 +10,73 @@ pub fn cosine_similarity(vec_a: &[f32], vec_b: &[f32]) -> f32 {
     }
 
     // Compute dot product: A · B
-    let dot_product: f32 = vec_a
-        .iter()
-        .zip(vec_b.iter())
-        .map(|(a, b)| a * b)
-        .sum();
+    let dot = dot_product(vec_a, vec_b);
 
     // Compute magnitudes: ||A|| and ||B||
-    let magnitude_a: f32 = vec_a.iter().map(|x| x * x).sum::<f32>().sqrt();
-    let magnitude_b: f32 = vec_b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let magnitude_a: f32 = dot_product(vec_a, vec_a).sqrt();
+    let magnitude_b: f32 = dot_product(vec_b, vec_b).sqrt();
 
     // Handle zero magnitude case
     if magnitude_a == 0.0 || magnitude_b == 0.0 {
         return 0.0;
     }
 
-    dot_product / (magnitude_a * magnitude_b)
+    dot / (magnitude_a * magnitude_b)
+}
+
+/// Number of independent accumulators in `dot_product`, one per SIMD lane
+const DOT_LANES: usize = 8;
+
+/// Dot product of two dense vectors; the cosine similarity when both are
+/// L2-normalized (see `l2_normalize`)
+///
+/// Sums into `DOT_LANES` independent accumulators so the compiler can
+/// vectorize the loop. Vectors of different lengths give 0.0.
+pub fn dot_product(vec_a: &[f32], vec_b: &[f32]) -> f32 {
+    if vec_a.len() != vec_b.len() {
+        return 0.0;
+    }
+
+    let chunks_a = vec_a.chunks_exact(DOT_LANES);
+    let chunks_b = vec_b.chunks_exact(DOT_LANES);
+    let tail: f32 = chunks_a
+        .remainder()
+        .iter()
+        .zip(chunks_b.remainder())
+        .map(|(a, b)| a * b)
+        .sum();
+
+    let mut lanes = [0.0f32; DOT_LANES];
+    for (a, b) in chunks_a.zip(chunks_b) {
+        for ((lane, a), b) in lanes.iter_mut().zip(a).zip(b) {
+            *lane += a * b;
+        }
+    }
+
+    lanes.iter().sum::<f32>() + tail
+}
+
+/// Dot product of two HashMap-based vectors; the cosine similarity when both
+/// are L2-normalized (see `compute_normalized_tfidf_vector`)
+pub fn compute_dot_product(vec_a: &HashMap<String, f32>, vec_b: &HashMap<String, f32>) -> f32 {
+    // Iterate the smaller map, look up in the larger
+    let (small, large) = if vec_a.len() <= vec_b.len() { (vec_a, vec_b) } else { (vec_b, vec_a) };
+
+    sum_in_term_order(
+        small
+            .iter()
+            .filter_map(|(term, a_val)| large.get(term).map(|b_val| (term, a_val * b_val))),
+    )
+}
+
+/// Sum per-term values in term order
+///
+/// HashMap iteration order differs between map instances, and float addition
+/// is not associative; summing in a fixed order gives bit-identical results.
+pub(crate) fn sum_in_term_order<'a>(values: impl Iterator<Item = (&'a String, f32)>) -> f32 {
+    let mut values: Vec<(&String, f32)> = values.collect();
+    values.sort_unstable_by_key(|&(term, _)| term);
+    values.into_iter().map(|(_, value)| value).sum()
 }
 
 /// Compute cosine similarity between two HashMap-based TF-IDF vectors
@@ -44,16 +95,27 @@ pub fn compute_cosine_similarity(
         return 0.0;
     }
 
-    // Compute dot product for common terms
-    let dot_product: f32 = vec_a
+    // The entries of both vectors, sorted once, so the dot product and both
+    // magnitudes are summed in term order in a single pass. A common term
+    // sorts into two adjacent entries
+    let mut entries: Vec<(&String, f32, f32)> = vec_a
         .iter()
-        .filter_map(|(term, a_val)| vec_b.get(term).map(|b_val| a_val * b_val))
-        .sum();
+        .map(|(term, &a_val)| (term, a_val, 0.0))
+        .chain(vec_b.iter().map(|(term, &b_val)| (term, 0.0, b_val)))
+        .collect();
+    entries.sort_unstable_by_key(|&(term, _, _)| term);
 
-    // Compute magnitudes
-    let magnitude_a: f32 = vec_a.values().map(|x| x * x).sum::<f32>().sqrt();
-    let magnitude_b: f32 = vec_b.values().map(|x| x * x).sum::<f32>().sqrt();
+    let (mut dot_product, mut norm_a, mut norm_b) = (0.0f32, 0.0f32, 0.0f32);
+    for term in entries.chunk_by(|x, y| x.0 == y.0) {
+        let a_val: f32 = term.iter().map(|&(_, a_val, _)| a_val).sum();
+        let b_val: f32 = term.iter().map(|&(_, _, b_val)| b_val).sum();
+        dot_product += a_val * b_val;
+        norm_a += a_val * a_val;
+        norm_b += b_val * b_val;
+    }
 
+    let magnitude_a = norm_a.sqrt();
+    let magnitude_b = norm_b.sqrt();
     if magnitude_a == 0.0 || magnitude_b == 0.0 {
         return 0.0;
     }
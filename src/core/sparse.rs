@@ -0,0 +1,207 @@
+//! Interned term ids and sparse TF-IDF vectors
+
+use std::collections::HashMap;
+
+/// Maps terms to dense `u32` ids, in order of first appearance
+#[derive(Debug, Clone, Default)]
+pub struct Vocabulary {
+    ids: HashMap<String, u32>,
+    terms: Vec<String>,
+}
+
+impl Vocabulary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Id of `term`, assigning the next free id to a new term
+    pub fn intern(&mut self, term: &str) -> u32 {
+        if let Some(&id) = self.ids.get(term) {
+            return id;
+        }
+
+        let id = self.terms.len() as u32;
+        self.ids.insert(term.to_string(), id);
+        self.terms.push(term.to_string());
+        id
+    }
+
+    /// Ids of a token sequence, interning new terms
+    pub fn intern_all(&mut self, tokens: &[String]) -> Vec<u32> {
+        tokens.iter().map(|token| self.intern(token)).collect()
+    }
+
+    /// Id of a known term
+    pub fn id(&self, term: &str) -> Option<u32> {
+        self.ids.get(term).copied()
+    }
+
+    /// Term of a known id
+    pub fn term(&self, id: u32) -> Option<&str> {
+        self.terms.get(id as usize).map(String::as_str)
+    }
+
+    /// Number of distinct terms
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+}
+
+/// Sparse vector with entries sorted by term id and a precomputed L2 norm
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SparseVector {
+    indices: Vec<u32>,
+    values: Vec<f32>,
+    norm: f32,
+}
+
+impl SparseVector {
+    /// Build a vector from `(term id, value)` entries in any order; values of
+    /// repeated ids are summed
+    pub fn from_entries(mut entries: Vec<(u32, f32)>) -> Self {
+        entries.sort_unstable_by_key(|&(id, _)| id);
+
+        let mut indices: Vec<u32> = Vec::with_capacity(entries.len());
+        let mut values: Vec<f32> = Vec::with_capacity(entries.len());
+        for (id, value) in entries {
+            if indices.last() == Some(&id) {
+                *values.last_mut().unwrap() += value;
+            } else {
+                indices.push(id);
+                values.push(value);
+            }
+        }
+
+        Self::from_sorted(indices, values)
+    }
+
+    /// Term frequencies of a token id sequence: count / number of tokens
+    pub fn term_frequencies(ids: &[u32]) -> Self {
+        if ids.is_empty() {
+            return Self::default();
+        }
+
+        let mut sorted = ids.to_vec();
+        sorted.sort_unstable();
+
+        let total = ids.len() as f32;
+        let mut indices: Vec<u32> = Vec::new();
+        let mut values: Vec<f32> = Vec::new();
+        for chunk in sorted.chunk_by(|a, b| a == b) {
+            indices.push(chunk[0]);
+            values.push(chunk.len() as f32 / total);
+        }
+
+        Self::from_sorted(indices, values)
+    }
+
+    fn from_sorted(indices: Vec<u32>, values: Vec<f32>) -> Self {
+        let norm = values.iter().map(|x| x * x).sum::<f32>().sqrt();
+        Self { indices, values, norm }
+    }
+
+    /// Term ids, ascending
+    pub fn indices(&self) -> &[u32] {
+        &self.indices
+    }
+
+    /// Values, aligned with `indices`
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+
+    /// L2 norm
+    pub fn norm(&self) -> f32 {
+        self.norm
+    }
+
+    /// Number of stored entries
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Multiply every entry by the weight of its term id (e.g. IDF)
+    pub fn weighted(&self, weights: &[f32]) -> Self {
+        let values = self
+            .indices
+            .iter()
+            .zip(&self.values)
+            .map(|(&id, value)| value * weights.get(id as usize).copied().unwrap_or(0.0))
+            .collect();
+
+        Self::from_sorted(self.indices.clone(), values)
+    }
+
+    /// The same vector scaled to unit L2 norm; a zero vector stays zero
+    ///
+    /// Between normalized vectors `dot` is the cosine similarity.
+    pub fn normalized(&self) -> Self {
+        if self.norm == 0.0 || self.norm == 1.0 {
+            return self.clone();
+        }
+
+        let values = self.values.iter().map(|value| value / self.norm).collect();
+        Self::from_sorted(self.indices.clone(), values)
+    }
+
+    /// Dot product by merge-joining the sorted term ids
+    pub fn dot(&self, other: &SparseVector) -> f32 {
+        let (mut i, mut j) = (0, 0);
+        let mut dot = 0.0;
+
+        while i < self.indices.len() && j < other.indices.len() {
+            match self.indices[i].cmp(&other.indices[j]) {
+                std::cmp::Ordering::Less => i += 1,
+                std::cmp::Ordering::Greater => j += 1,
+                std::cmp::Ordering::Equal => {
+                    dot += self.values[i] * other.values[j];
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+
+        dot
+    }
+
+    /// Cosine similarity using the precomputed norms
+    pub fn cosine(&self, other: &SparseVector) -> f32 {
+        if self.norm == 0.0 || other.norm == 0.0 {
+            return 0.0;
+        }
+
+        self.dot(other) / (self.norm * other.norm)
+    }
+}
+
+/// Smoothed IDF of every term id: log((N + 1) / (df + 1)) + 1, where N is
+/// the number of vectors and df the number of vectors containing the term
+///
+/// The result is indexed by term id and has `vocabulary_size` entries.
+pub fn compute_sparse_idf<'a>(
+    vectors: impl IntoIterator<Item = &'a SparseVector>,
+    vocabulary_size: usize,
+) -> Vec<f32> {
+    let mut document_frequency = vec![0u32; vocabulary_size];
+    let mut count = 0usize;
+    for vector in vectors {
+        count += 1;
+        for &id in vector.indices() {
+            document_frequency[id as usize] += 1;
+        }
+    }
+
+    let n = count as f32;
+    document_frequency
+        .into_iter()
+        .map(|df| ((n + 1.0) / (df as f32 + 1.0)).ln() + 1.0)
+        .collect()
+}
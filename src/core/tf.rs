@@ -4,7 +4,6 @@ use std::collections::HashMap;
 
 /// Computes Term Frequency (TF) for a list of tokens.
 /// TF = (number of times term appears) / (total number of terms)
-
 pub fn compute_tf(tokens: &[String]) -> HashMap<String, f32> {
     if tokens.is_empty() {
         return HashMap::new();
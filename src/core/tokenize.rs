@@ -1,10 +1,90 @@
 //! Tokenization - pure function
 
-/// Tokenizes text into a vector of words by splitting on whitespace.
+/// How text is cut into terms
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TokenizerMode {
+    /// Whitespace-separated words, with runs of Chinese and Japanese characters cut into
+    /// character bigrams
+    #[default]
+    Auto,
+    /// Whitespace-separated words only
+    Whitespace,
+    /// Overlapping character bigrams of every word
+    CharBigrams,
+}
 
+/// Tokenizes text into a vector of terms.
+///
+/// Words are split on whitespace. Chinese and Japanese text has no spaces
+/// between words, so runs of CJK characters become overlapping
+/// character bigrams ("相似度" → "相似", "似度"); a lone CJK character is kept
+/// as a unigram.
 pub fn tokenize(text: &str) -> Vec<String> {
-    text.split_whitespace()
-        .filter(|s| !s.is_empty())
-        .map(|s| s.to_string())
-        .collect()
+    tokenize_with_mode(text, TokenizerMode::Auto)
+}
+
+/// Tokenizes text into a vector of terms using the given mode.
+pub fn tokenize_with_mode(text: &str, mode: TokenizerMode) -> Vec<String> {
+    let mut tokens = Vec::new();
+
+    for word in text.split_whitespace() {
+        match mode {
+            TokenizerMode::Whitespace => tokens.push(word.to_string()),
+            TokenizerMode::CharBigrams => push_bigrams(word, &mut tokens),
+            TokenizerMode::Auto => push_mixed(word, &mut tokens),
+        }
+    }
+
+    tokens
+}
+
+/// Split a word into runs of CJK and non-CJK characters; non-CJK runs stay
+/// whole, CJK runs become bigrams
+fn push_mixed(word: &str, tokens: &mut Vec<String>) {
+    let mut run_start = 0;
+    let mut run_is_cjk = None;
+
+    for (index, c) in word.char_indices() {
+        let cjk = is_cjk(c);
+        if run_is_cjk.is_some_and(|previous| previous != cjk) {
+            push_run(&word[run_start..index], run_is_cjk == Some(true), tokens);
+            run_start = index;
+        }
+        run_is_cjk = Some(cjk);
+    }
+    push_run(&word[run_start..], run_is_cjk == Some(true), tokens);
+}
+
+fn push_run(run: &str, cjk: bool, tokens: &mut Vec<String>) {
+    if run.is_empty() {
+        return;
+    }
+    if cjk {
+        push_bigrams(run, tokens);
+    } else {
+        tokens.push(run.to_string());
+    }
+}
+
+fn push_bigrams(run: &str, tokens: &mut Vec<String>) {
+    let chars: Vec<char> = run.chars().collect();
+    if chars.len() < 2 {
+        tokens.push(run.to_string());
+        return;
+    }
+    tokens.extend(chars.windows(2).map(|pair| pair.iter().collect::<String>()));
+}
+
+/// Han, Hiragana and Katakana characters (Korean separates words with spaces)
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c,
+        '\u{3040}'..='\u{309F}'
+            | '\u{30A0}'..='\u{30FF}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{FF66}'..='\u{FF9F}'
+            | '\u{20000}'..='\u{2FA1F}'
+    )
 }
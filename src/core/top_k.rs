@@ -0,0 +1,58 @@
+//! Bounded selection of the first items in sort order
+
+use std::collections::BinaryHeap;
+
+/// Keeps the `capacity` smallest items seen so far, i.e. the ones a full
+/// ascending sort would put first
+///
+/// Memory stays bounded by `capacity` no matter how many items are pushed,
+/// and no full sort of the input is needed.
+#[derive(Debug)]
+pub(crate) struct TopK<T: Ord> {
+    capacity: usize,
+    // Max-heap: the worst kept item sits at the top, ready to be evicted
+    heap: BinaryHeap<T>,
+}
+
+impl<T: Ord> TopK<T> {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            heap: BinaryHeap::with_capacity(capacity.min(1024) + 1),
+        }
+    }
+
+    /// Offer an item; it is kept only if it ranks among the first `capacity`
+    ///
+    /// Returns the item that is not kept: `item` itself, or the one it evicted.
+    pub(crate) fn push(&mut self, item: T) -> Option<T> {
+        if self.capacity == 0 {
+            return Some(item);
+        }
+
+        if self.heap.len() < self.capacity {
+            self.heap.push(item);
+            None
+        } else if self.heap.peek().is_some_and(|worst| item < *worst) {
+            let evicted = self.heap.pop();
+            self.heap.push(item);
+            evicted
+        } else {
+            Some(item)
+        }
+    }
+
+    /// The worst kept item once `capacity` items are kept; a new item has to
+    /// rank before it to be kept
+    pub(crate) fn worst_kept(&self) -> Option<&T> {
+        if self.heap.len() < self.capacity {
+            return None;
+        }
+        self.heap.peek()
+    }
+
+    /// The kept items in ascending order
+    pub(crate) fn into_sorted_vec(self) -> Vec<T> {
+        self.heap.into_sorted_vec()
+    }
+}
@@ -2,6 +2,8 @@
 
 use std::collections::HashMap;
 
+use super::similarity::sum_in_term_order;
+
 /// Converts TF and IDF into a TF-IDF vector based on vocabulary order.
 pub fn vectorize(
     tf: &HashMap<String, f32>,
@@ -18,6 +20,28 @@ pub fn vectorize(
         .collect()
 }
 
+/// TF-IDF vector in vocabulary order, scaled to unit length
+///
+/// Similarities between such vectors are plain dot products
+/// (`dot_product`), with no magnitudes to recompute per pair.
+pub fn vectorize_normalized(
+    tf: &HashMap<String, f32>,
+    idf: &HashMap<String, f32>,
+    vocabulary: &[String],
+) -> Vec<f32> {
+    let mut vector = vectorize(tf, idf, vocabulary);
+    l2_normalize(&mut vector);
+    vector
+}
+
+/// Scale a vector in place to unit L2 norm; a zero vector is left unchanged
+pub fn l2_normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+}
+
 /// Compute TF-IDF vector directly as HashMap (for sentence-level analysis)
 pub fn compute_tfidf_vector(
     tf: &HashMap<String, f32>,
@@ -30,3 +54,16 @@ pub fn compute_tfidf_vector(
         })
         .collect()
 }
+
+/// HashMap TF-IDF vector scaled to unit L2 norm (for `compute_dot_product`)
+pub fn compute_normalized_tfidf_vector(
+    tf: &HashMap<String, f32>,
+    idf: &HashMap<String, f32>,
+) -> HashMap<String, f32> {
+    let mut vector = compute_tfidf_vector(tf, idf);
+    let norm = sum_in_term_order(vector.iter().map(|(term, x)| (term, x * x))).sqrt();
+    if norm > 0.0 {
+        vector.values_mut().for_each(|x| *x /= norm);
+    }
+    vector
+}
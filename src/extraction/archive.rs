@@ -0,0 +1,179 @@
+//! ZIP archive expansion into individual documents
+
+use std::io::{Cursor, Read};
+
+use thiserror::Error;
+use zip::ZipArchive;
+
+use super::{detect_file_type, FileType};
+
+/// Limits applied while expanding an uploaded archive
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArchiveLimits {
+    /// Maximum number of entries (files and directories) across all nested archives
+    pub max_entries: usize,
+    /// Maximum total uncompressed size of the expanded documents, in bytes
+    pub max_total_size: usize,
+    /// Maximum archive nesting depth (1 = no nested archives)
+    pub max_depth: usize,
+}
+
+impl Default for ArchiveLimits {
+    fn default() -> Self {
+        Self {
+            max_entries: 1000,
+            max_total_size: 50 * 1024 * 1024,
+            max_depth: 3,
+        }
+    }
+}
+
+/// A document extracted from an archive
+#[derive(Debug, Clone)]
+pub struct ArchiveEntry {
+    /// Path inside the archive; entries of nested archives are prefixed with the nested archive path
+    pub path: String,
+    pub data: Vec<u8>,
+}
+
+/// Result of expanding an archive
+#[derive(Debug, Clone, Default)]
+pub struct ExpandedArchive {
+    /// Supported documents in archive order
+    pub entries: Vec<ArchiveEntry>,
+    /// Paths of entries that were skipped (unsupported type or unsafe path)
+    pub skipped: Vec<String>,
+}
+
+/// Errors that can occur while expanding an archive
+#[derive(Debug, Error)]
+pub enum ArchiveError {
+    #[error("invalid ZIP archive: {0}")]
+    Invalid(String),
+
+    #[error("archive contains more than {0} entries")]
+    TooManyEntries(usize),
+
+    #[error("archive expands to more than {0} bytes")]
+    TooLarge(usize),
+
+    #[error("archive nesting exceeds maximum depth of {0}")]
+    TooDeep(usize),
+}
+
+/// Expand a ZIP archive into its supported documents
+///
+/// Nested archives (`.zip` entries, or other entries whose content is a plain
+/// ZIP) are expanded recursively up to `limits.max_depth`.
+/// Directories, hidden files, macOS resource forks, entries with unsafe
+/// paths and entries with an unsupported or no extension are skipped. Entry
+/// sizes are enforced on the bytes actually decompressed, not on the sizes
+/// declared in the archive headers.
+pub fn expand_archive(file_bytes: &[u8], limits: &ArchiveLimits) -> Result<ExpandedArchive, ArchiveError> {
+    let mut expanded = ExpandedArchive::default();
+    let mut budget = Budget { entries: 0, bytes: 0 };
+
+    expand_into(file_bytes, "", 1, limits, &mut budget, &mut expanded)?;
+
+    Ok(expanded)
+}
+
+/// Entries and bytes consumed so far across nested archives
+struct Budget {
+    entries: usize,
+    bytes: usize,
+}
+
+fn expand_into(
+    file_bytes: &[u8],
+    prefix: &str,
+    depth: usize,
+    limits: &ArchiveLimits,
+    budget: &mut Budget,
+    expanded: &mut ExpandedArchive,
+) -> Result<(), ArchiveError> {
+    if depth > limits.max_depth {
+        return Err(ArchiveError::TooDeep(limits.max_depth));
+    }
+
+    let mut archive =
+        ZipArchive::new(Cursor::new(file_bytes)).map_err(|e| ArchiveError::Invalid(e.to_string()))?;
+
+    for index in 0..archive.len() {
+        budget.entries += 1;
+        if budget.entries > limits.max_entries {
+            return Err(ArchiveError::TooManyEntries(limits.max_entries));
+        }
+
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|e| ArchiveError::Invalid(e.to_string()))?;
+
+        if entry.is_dir() {
+            continue;
+        }
+
+        let raw_name = entry.name().to_string();
+        let path = match entry.enclosed_name() {
+            Some(path) => path.to_string_lossy().replace('\\', "/"),
+            None => {
+                expanded.skipped.push(format!("{}{}", prefix, raw_name));
+                continue;
+            }
+        };
+        let label = format!("{}{}", prefix, path);
+
+        let is_archive = FileType::from_filename(&path) == Some(FileType::Zip);
+        let is_supported = is_supported_name(&path);
+        // Entries without an extension are only read to find nested archives
+        if is_ignored(&path) || !(is_archive || is_supported || !has_extension(&path)) {
+            expanded.skipped.push(label);
+            continue;
+        }
+
+        // Read at most one byte past the remaining budget to detect overflow
+        let remaining = limits.max_total_size.saturating_sub(budget.bytes);
+        let mut data = Vec::new();
+        entry
+            .by_ref()
+            .take(remaining as u64 + 1)
+            .read_to_end(&mut data)
+            .map_err(|e| ArchiveError::Invalid(format!("cannot read '{}': {}", label, e)))?;
+        if data.len() > remaining {
+            return Err(ArchiveError::TooLarge(limits.max_total_size));
+        }
+
+        // A nested archive without the `.zip` extension is found by its content
+        let is_archive = is_archive
+            || detect_file_type(&data, &path, None).is_some_and(|detection| detection.file_type == FileType::Zip);
+        if is_archive {
+            expand_into(&data, &format!("{}/", label), depth + 1, limits, budget, expanded)?;
+        } else if !is_supported {
+            expanded.skipped.push(label);
+        } else {
+            budget.bytes += data.len();
+            expanded.entries.push(ArchiveEntry { path: label, data });
+        }
+    }
+
+    Ok(())
+}
+
+/// Metadata and hidden files that never contain submissions
+fn is_ignored(path: &str) -> bool {
+    path.split('/')
+        .any(|component| component.starts_with('.') || component == "__MACOSX")
+}
+
+/// Entries with the extension of a supported document
+fn is_supported_name(path: &str) -> bool {
+    FileType::from_filename(file_name(path)).is_some()
+}
+
+fn has_extension(path: &str) -> bool {
+    file_name(path).contains('.')
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
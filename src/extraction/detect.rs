@@ -0,0 +1,207 @@
+//! File type detection from content (magic bytes), Content-Type and extension
+
+use std::io::{Cursor, Read};
+
+use zip::ZipArchive;
+
+use super::FileType;
+
+/// Number of leading bytes (BOM and whitespace) allowed before the `%PDF-` header
+const PDF_HEADER_WINDOW: usize = 1024;
+
+/// Where a detected file type came from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DetectionSource {
+    /// Magic bytes or package structure of the file content
+    Content,
+    /// The multipart `Content-Type` of the upload
+    ContentType,
+    /// The filename extension
+    Extension,
+}
+
+/// Result of file type detection
+#[derive(Debug, Clone, PartialEq)]
+pub struct Detection {
+    pub file_type: FileType,
+    pub source: DetectionSource,
+    /// Human-readable note when the extension or Content-Type disagrees with the content
+    pub mismatch: Option<String>,
+}
+
+/// What the content alone tells about a file
+enum Sniffed {
+    /// The content unambiguously identifies the type
+    Known(FileType),
+    /// Decodable text: plain text, Markdown and HTML cannot be told apart reliably
+    Text,
+    /// Binary content with no recognized signature
+    Unknown,
+    /// A recognized format that cannot be extracted (e.g. XLSX, ODS)
+    Unsupported,
+}
+
+/// Detect the file type of an upload
+///
+/// Content signatures (`%PDF-`, `{\rtf`, ZIP packages containing
+/// `word/document.xml`, `ppt/presentation.xml` or an OpenDocument
+/// `mimetype`, other ZIP files as archives) take precedence. The extension,
+/// and then the `Content-Type`, are only used when the content is ambiguous
+/// (text) or unrecognized. Returns `None` when no source yields a supported
+/// type, and for office packages of other kinds (spreadsheets, drawings)
+/// unless the name ends in `.zip`.
+pub fn detect_file_type(
+    file_bytes: &[u8],
+    filename: &str,
+    content_type: Option<&str>,
+) -> Option<Detection> {
+    let by_extension = FileType::from_filename(filename);
+    let by_content_type = content_type.and_then(FileType::from_mime);
+    let claimed = by_extension
+        .map(|t| (t, DetectionSource::Extension))
+        .or(by_content_type.map(|t| (t, DetectionSource::ContentType)));
+
+    match sniff_content(file_bytes, by_extension == Some(FileType::Zip)) {
+        Sniffed::Known(file_type) => Some(Detection {
+            file_type,
+            source: DetectionSource::Content,
+            mismatch: claimed
+                .filter(|(claimed_type, _)| *claimed_type != file_type)
+                .map(|(claimed_type, source)| {
+                    describe_mismatch(filename, claimed_type, source, file_type)
+                }),
+        }),
+        Sniffed::Text => {
+            let text_claim = [
+                by_extension.map(|t| (t, DetectionSource::Extension)),
+                by_content_type.map(|t| (t, DetectionSource::ContentType)),
+            ]
+            .into_iter()
+            .flatten()
+            .find(|(t, _)| t.is_text());
+
+            match text_claim {
+                Some((file_type, source)) => Some(Detection {
+                    file_type,
+                    source,
+                    mismatch: None,
+                }),
+                None => {
+                    let file_type = if looks_like_html(file_bytes) {
+                        FileType::Html
+                    } else {
+                        FileType::Txt
+                    };
+                    Some(Detection {
+                        file_type,
+                        source: DetectionSource::Content,
+                        mismatch: claimed.map(|(claimed_type, source)| {
+                            describe_mismatch(filename, claimed_type, source, file_type)
+                        }),
+                    })
+                }
+            }
+        }
+        Sniffed::Unknown => claimed.map(|(file_type, source)| Detection {
+            file_type,
+            source,
+            mismatch: None,
+        }),
+        Sniffed::Unsupported => None,
+    }
+}
+
+/// Identify the type from magic bytes and package structure
+///
+/// `named_zip` tells whether the filename has the `.zip` extension.
+fn sniff_content(file_bytes: &[u8], named_zip: bool) -> Sniffed {
+    if has_pdf_header(file_bytes) {
+        return Sniffed::Known(FileType::Pdf);
+    }
+    if file_bytes.starts_with(b"{\\rtf") {
+        return Sniffed::Known(FileType::Rtf);
+    }
+    if file_bytes.starts_with(b"PK\x03\x04") || file_bytes.starts_with(b"PK\x05\x06") {
+        return sniff_package(file_bytes, named_zip);
+    }
+    if file_bytes.starts_with(b"\xEF\xBB\xBF")
+        || file_bytes.starts_with(b"\xFF\xFE")
+        || file_bytes.starts_with(b"\xFE\xFF")
+    {
+        return Sniffed::Text;
+    }
+    if !file_bytes.contains(&0) && std::str::from_utf8(file_bytes).is_ok() {
+        return Sniffed::Text;
+    }
+
+    Sniffed::Unknown
+}
+
+/// Whether the file starts with `%PDF-`, after at most a BOM and whitespace
+fn has_pdf_header(file_bytes: &[u8]) -> bool {
+    let head = &file_bytes[..file_bytes.len().min(PDF_HEADER_WINDOW)];
+    let head = head.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(head);
+    let start = head.iter().position(|byte| !byte.is_ascii_whitespace()).unwrap_or(head.len());
+    head[start..].starts_with(b"%PDF-")
+}
+
+/// Identify an office document from the entries of a ZIP package
+///
+/// A package with no office markers is a plain archive. OOXML and
+/// OpenDocument packages of unsupported kinds are unsupported, unless the
+/// name says `.zip`.
+fn sniff_package(file_bytes: &[u8], named_zip: bool) -> Sniffed {
+    let Ok(mut archive) = ZipArchive::new(Cursor::new(file_bytes)) else {
+        return Sniffed::Unknown;
+    };
+
+    // OpenDocument packages declare their type in a `mimetype` entry
+    let mut is_office = false;
+    if let Ok(mut entry) = archive.by_name("mimetype") {
+        is_office = true;
+        let mut mimetype = String::new();
+        if entry.read_to_string(&mut mimetype).is_ok() {
+            if let Some(file_type @ (FileType::Odt | FileType::Odp)) = FileType::from_mime(mimetype.trim()) {
+                return Sniffed::Known(file_type);
+            }
+        }
+    }
+
+    let has = |name: &str| archive.file_names().any(|entry| entry == name);
+    if has("word/document.xml") {
+        Sniffed::Known(FileType::Docx)
+    } else if has("ppt/presentation.xml") {
+        Sniffed::Known(FileType::Pptx)
+    } else if (is_office || has("[Content_Types].xml") || has("META-INF/manifest.xml")) && !named_zip {
+        Sniffed::Unsupported
+    } else {
+        Sniffed::Known(FileType::Zip)
+    }
+}
+
+/// Whether decoded text starts like an HTML document
+fn looks_like_html(file_bytes: &[u8]) -> bool {
+    let head = String::from_utf8_lossy(&file_bytes[..file_bytes.len().min(512)]).to_lowercase();
+    let head = head.trim_start_matches('\u{feff}').trim_start();
+    head.starts_with("<!doctype html") || head.starts_with("<html")
+}
+
+/// Warning text for a claimed type that disagrees with the content
+fn describe_mismatch(
+    filename: &str,
+    claimed: FileType,
+    source: DetectionSource,
+    actual: FileType,
+) -> String {
+    let claim = match source {
+        DetectionSource::ContentType => "Content-Type",
+        _ => "extension",
+    };
+    format!(
+        "'{}': {} indicates {} but content is {}",
+        filename,
+        claim,
+        claimed.name(),
+        actual.name()
+    )
+}
@@ -3,7 +3,9 @@
 /// Extract text from DOCX file bytes
 ///
 /// Uses docx-rs library to parse DOCX (Open XML format) and extract text content.
-/// Returns concatenated text from all paragraphs.
+/// Returns concatenated text from all paragraphs. Paragraphs with a quote
+/// style (block quotes) are wrapped in curly quotes so quotation detection
+/// recognizes them.
 pub fn extract_docx(file_bytes: &[u8]) -> Result<String, String> {
     docx_rs::read_docx(file_bytes)
         .map_err(|e| format!("Failed to extract DOCX: {}", e))
@@ -49,6 +51,8 @@ pub fn extract_docx(file_bytes: &[u8]) -> Result<String, String> {
                             
                             if para_text.trim().is_empty() {
                                 None
+                            } else if is_quote_style(&para.property) {
+                                Some(format!("\u{201C}{}\u{201D}", para_text.trim()))
                             } else {
                                 Some(para_text)
                             }
@@ -61,3 +65,11 @@ pub fn extract_docx(file_bytes: &[u8]) -> Result<String, String> {
             text_parts.join("\n")
         })
 }
+
+/// Whether a paragraph uses a quotation style such as "Quote" or "Intense Quote"
+fn is_quote_style(property: &docx_rs::ParagraphProperty) -> bool {
+    property.style.as_ref().is_some_and(|style| {
+        let id = style.val.to_lowercase();
+        id.contains("quote") || id == "blocktext"
+    })
+}
@@ -0,0 +1,142 @@
+//! HTML text extraction module
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::txt::decode_text;
+use super::ExtractionOptions;
+
+lazy_static! {
+    /// Comments, scripts, styles and other non-content elements
+    static ref NON_CONTENT: Regex = Regex::new(
+        r"(?is)<!--.*?-->|<(script|style|noscript|template|head|svg)\b[^>]*>.*?</(script|style|noscript|template|head|svg)\s*>"
+    )
+    .unwrap();
+
+    /// Preformatted / code blocks, removed when `strip_code` is enabled
+    static ref CODE_BLOCK: Regex = Regex::new(r"(?is)<pre\b[^>]*>.*?</pre\s*>").unwrap();
+
+    /// Block-level tags that end a paragraph (and therefore a sentence)
+    static ref BLOCK_TAG: Regex = Regex::new(
+        r"(?i)</?(p|div|h[1-6]|li|ul|ol|br|hr|tr|td|th|table|thead|tbody|section|article|header|footer|nav|aside|main|blockquote|pre|dl|dt|dd|figure|figcaption|caption|title)\b[^>]*>"
+    )
+    .unwrap();
+
+    /// Any remaining (inline) tag
+    static ref ANY_TAG: Regex = Regex::new(r"(?s)<[^>]*>").unwrap();
+
+    /// Character and numeric entity references
+    static ref ENTITY: Regex = Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z][a-zA-Z0-9]*);").unwrap();
+
+    /// Paragraph separator used between extracted blocks
+    static ref BLOCK_SEPARATOR: Regex = Regex::new(r"\n[ \t]*\n").unwrap();
+}
+
+/// Extract text from HTML file bytes
+///
+/// Removes scripts, styles, comments and markup, decodes entities and
+/// keeps block elements (headings, paragraphs, list items) as separate
+/// paragraphs so they end up as separate sentences.
+pub fn extract_html(file_bytes: &[u8], options: &ExtractionOptions) -> Result<String, String> {
+    let html = decode_text(file_bytes, "HTML")?;
+
+    Ok(html_to_text(&html, options))
+}
+
+/// Convert an HTML fragment into plain text paragraphs separated by blank lines
+pub(crate) fn html_to_text(html: &str, options: &ExtractionOptions) -> String {
+    let without_non_content = NON_CONTENT.replace_all(html, " ");
+
+    let without_code = if options.strip_code {
+        CODE_BLOCK.replace_all(&without_non_content, "\n\n")
+    } else {
+        without_non_content
+    };
+
+    let with_breaks = BLOCK_TAG.replace_all(&without_code, "\n\n");
+    let text = ANY_TAG.replace_all(&with_breaks, "");
+
+    join_blocks(&decode_entities(&text))
+}
+
+/// Collapse whitespace inside each paragraph and join paragraphs with a blank line
+pub(crate) fn join_blocks(text: &str) -> String {
+    BLOCK_SEPARATOR
+        .split(text)
+        .map(|block| block.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|block| !block.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Decode HTML character references (`&amp;`, `&#8217;`, `&#x2019;`, ...)
+///
+/// Unknown named entities are left untouched.
+pub(crate) fn decode_entities(text: &str) -> String {
+    ENTITY
+        .replace_all(text, |caps: &regex::Captures| {
+            let entity = &caps[1];
+            let decoded = if let Some(hex) = entity.strip_prefix("#x").or(entity.strip_prefix("#X")) {
+                u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+            } else if let Some(dec) = entity.strip_prefix('#') {
+                dec.parse::<u32>().ok().and_then(char::from_u32)
+            } else {
+                named_entity(entity)
+            };
+
+            decoded
+                .map(|c| c.to_string())
+                .unwrap_or_else(|| caps[0].to_string())
+        })
+        .into_owned()
+}
+
+/// Lookup for the named entities that commonly appear in prose
+fn named_entity(name: &str) -> Option<char> {
+    let c = match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        "ndash" => '–',
+        "mdash" => '—',
+        "hellip" => '…',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "laquo" => '«',
+        "raquo" => '»',
+        "bull" => '•',
+        "middot" => '·',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "deg" => '°',
+        "euro" => '€',
+        "pound" => '£',
+        "yen" => '¥',
+        "cent" => '¢',
+        "sect" => '§',
+        "para" => '¶',
+        "times" => '×',
+        "divide" => '÷',
+        "eacute" => 'é',
+        "egrave" => 'è',
+        "aacute" => 'á',
+        "agrave" => 'à',
+        "iacute" => 'í',
+        "oacute" => 'ó',
+        "uacute" => 'ú',
+        "ntilde" => 'ñ',
+        "ccedil" => 'ç',
+        "auml" => 'ä',
+        "ouml" => 'ö',
+        "uuml" => 'ü',
+        "szlig" => 'ß',
+        _ => return None,
+    };
+    Some(c)
+}
@@ -0,0 +1,167 @@
+//! Markdown text extraction module
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::html::{decode_entities, join_blocks};
+use super::txt::decode_text;
+use super::ExtractionOptions;
+
+lazy_static! {
+    /// ATX heading: `## Heading ##`
+    static ref HEADING: Regex = Regex::new(r"^\s{0,3}#{1,6}(?:\s+(.*?))?\s*#*\s*$").unwrap();
+
+    /// Setext heading underline (`===` / `---`) or thematic break (`***`, `___`)
+    static ref RULE: Regex = Regex::new(r"^\s{0,3}(?:=+|-+|(?:[-*_]\s*){3,})\s*$").unwrap();
+
+    /// Bullet or numbered list item marker
+    static ref LIST_ITEM: Regex = Regex::new(r"^\s*(?:[-*+•]|\d+[.)])\s+").unwrap();
+
+    /// Table delimiter row: `| --- | :---: |`
+    static ref TABLE_DELIMITER: Regex = Regex::new(r"^\s*\|?\s*:?-+:?\s*(?:\|\s*:?-+:?\s*)*\|?\s*$").unwrap();
+
+    /// Link reference definition: `[id]: https://example.com`
+    static ref LINK_DEFINITION: Regex = Regex::new(r"^\s{0,3}\[[^\]]+\]:\s*\S+").unwrap();
+
+    /// Images `![alt](src)` are replaced by their alt text
+    static ref IMAGE: Regex = Regex::new(r"!\[([^\]]*)\]\([^)]*\)").unwrap();
+
+    /// Inline `[text](href)` and reference `[text][id]` links are replaced by their text
+    static ref LINK: Regex = Regex::new(r"\[([^\]]+)\](?:\([^)]*\)|\[[^\]]*\])").unwrap();
+
+    /// Autolinks `<https://example.com>`
+    static ref AUTOLINK: Regex = Regex::new(r"<(?:https?|mailto|ftp):[^>\s]*>").unwrap();
+
+    /// Inline HTML tags
+    static ref INLINE_TAG: Regex = Regex::new(r"</?[a-zA-Z][^>]*>").unwrap();
+
+    /// Emphasis, strikethrough and inline code markers
+    static ref EMPHASIS: Regex = Regex::new(r"\*{1,3}|~~|`+|(?:^|\b)_{1,3}|_{1,3}(?:\b|$)").unwrap();
+}
+
+/// Extract text from Markdown file bytes
+///
+/// Strips Markdown syntax (emphasis, links, images, tables, inline HTML) and,
+/// unless disabled in `options`, fenced and indented code blocks. Headings,
+/// list items and paragraphs are kept as separate paragraphs so they end up
+/// as separate sentences.
+pub fn extract_markdown(file_bytes: &[u8], options: &ExtractionOptions) -> Result<String, String> {
+    let markdown = decode_text(file_bytes, "Markdown")?;
+
+    Ok(markdown_to_text(&markdown, options))
+}
+
+/// Convert Markdown source into plain text paragraphs separated by blank lines
+fn markdown_to_text(markdown: &str, options: &ExtractionOptions) -> String {
+    let mut blocks: Vec<String> = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut fence: Option<&str> = None;
+
+    let flush = |current: &mut Vec<&str>, blocks: &mut Vec<String>| {
+        if !current.is_empty() {
+            blocks.push(current.join(" "));
+            current.clear();
+        }
+    };
+
+    for line in skip_front_matter(markdown).lines() {
+        let trimmed = line.trim();
+
+        // Inside a fenced code block: wait for the closing fence
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+                flush(&mut current, &mut blocks);
+            } else if !options.strip_code {
+                current.push(line);
+            }
+            continue;
+        }
+
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            flush(&mut current, &mut blocks);
+            fence = Some(&trimmed[..3]);
+            continue;
+        }
+
+        if trimmed.is_empty() {
+            flush(&mut current, &mut blocks);
+            continue;
+        }
+
+        // Indented code block (only when not continuing a paragraph)
+        if options.strip_code
+            && current.is_empty()
+            && (line.starts_with("    ") || line.starts_with('\t'))
+            && !LIST_ITEM.is_match(line)
+        {
+            continue;
+        }
+
+        if let Some(caps) = HEADING.captures(line) {
+            flush(&mut current, &mut blocks);
+            if let Some(heading) = caps.get(1) {
+                blocks.push(heading.as_str().to_string());
+            }
+            continue;
+        }
+
+        // Setext underline closes the heading above it; otherwise a thematic break
+        if RULE.is_match(line) {
+            flush(&mut current, &mut blocks);
+            continue;
+        }
+
+        if TABLE_DELIMITER.is_match(line) && trimmed.contains('-') && trimmed.contains('|') {
+            continue;
+        }
+
+        if LINK_DEFINITION.is_match(line) {
+            continue;
+        }
+
+        let content = trimmed.trim_start_matches(|c: char| c == '>' || c.is_whitespace());
+
+        if let Some(marker) = LIST_ITEM.find(content) {
+            flush(&mut current, &mut blocks);
+            current.push(&content[marker.end()..]);
+            continue;
+        }
+
+        current.push(content);
+    }
+    flush(&mut current, &mut blocks);
+
+    let text = blocks
+        .iter()
+        .map(|block| strip_inline(block))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    join_blocks(&decode_entities(&text))
+}
+
+/// Remove a leading YAML front matter block (`---` ... `---`)
+fn skip_front_matter(markdown: &str) -> &str {
+    let Some(rest) = markdown.strip_prefix("---\n").or(markdown.strip_prefix("---\r\n")) else {
+        return markdown;
+    };
+
+    match rest.find("\n---") {
+        Some(end) => {
+            let after = &rest[end + 4..];
+            after.find('\n').map(|nl| &after[nl + 1..]).unwrap_or("")
+        }
+        None => markdown,
+    }
+}
+
+/// Strip inline Markdown syntax from a single paragraph
+fn strip_inline(block: &str) -> String {
+    let text = IMAGE.replace_all(block, "$1");
+    let text = LINK.replace_all(&text, "$1");
+    let text = AUTOLINK.replace_all(&text, "");
+    let text = INLINE_TAG.replace_all(&text, "");
+    let text = EMPHASIS.replace_all(&text, "");
+    text.replace('|', " ")
+}
@@ -1,12 +1,29 @@
-//! File extraction modules for PDF, DOCX, and TXT files
+//! File extraction modules for PDF, DOCX, ODT, RTF, PPTX, ODP, TXT, HTML and Markdown files
 
 pub mod pdf;
 pub mod docx;
 pub mod txt;
+pub mod html;
+pub mod markdown;
+pub mod odt;
+pub mod odp;
+pub mod rtf;
+pub mod pptx;
+pub mod detect;
+pub mod archive;
+mod package;
 
 pub use self::pdf::extract_pdf;
 pub use self::docx::extract_docx;
 pub use self::txt::extract_txt;
+pub use self::html::extract_html;
+pub use self::markdown::extract_markdown;
+pub use self::odt::extract_odt;
+pub use self::odp::extract_odp;
+pub use self::rtf::extract_rtf;
+pub use self::pptx::extract_pptx;
+pub use self::detect::{detect_file_type, Detection, DetectionSource};
+pub use self::archive::{expand_archive, ArchiveEntry, ArchiveError, ArchiveLimits, ExpandedArchive};
 
 use std::path::Path;
 
@@ -16,6 +33,14 @@ pub enum FileType {
     Pdf,
     Docx,
     Txt,
+    Html,
+    Markdown,
+    Odt,
+    Rtf,
+    Pptx,
+    Odp,
+    /// ZIP archive of documents, expanded with [`expand_archive`] before extraction
+    Zip,
 }
 
 impl FileType {
@@ -25,6 +50,13 @@ impl FileType {
             "pdf" => Some(FileType::Pdf),
             "docx" => Some(FileType::Docx),
             "txt" => Some(FileType::Txt),
+            "html" | "htm" | "xhtml" => Some(FileType::Html),
+            "md" | "markdown" => Some(FileType::Markdown),
+            "odt" => Some(FileType::Odt),
+            "rtf" => Some(FileType::Rtf),
+            "pptx" => Some(FileType::Pptx),
+            "odp" => Some(FileType::Odp),
+            "zip" => Some(FileType::Zip),
             _ => None,
         }
     }
@@ -36,13 +68,86 @@ impl FileType {
             .and_then(|ext| ext.to_str())
             .and_then(Self::from_extension)
     }
+
+    /// Detect file type from a MIME type (multipart `Content-Type`)
+    pub fn from_mime(mime: &str) -> Option<Self> {
+        let essence = mime.split(';').next().unwrap_or("").trim().to_lowercase();
+        match essence.as_str() {
+            "application/pdf" => Some(FileType::Pdf),
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document" => {
+                Some(FileType::Docx)
+            }
+            "text/plain" => Some(FileType::Txt),
+            "text/html" | "application/xhtml+xml" => Some(FileType::Html),
+            "text/markdown" | "text/x-markdown" => Some(FileType::Markdown),
+            "application/vnd.oasis.opendocument.text" => Some(FileType::Odt),
+            "application/rtf" | "text/rtf" => Some(FileType::Rtf),
+            "application/vnd.openxmlformats-officedocument.presentationml.presentation" => {
+                Some(FileType::Pptx)
+            }
+            "application/vnd.oasis.opendocument.presentation" => Some(FileType::Odp),
+            "application/zip" | "application/x-zip-compressed" => Some(FileType::Zip),
+            _ => None,
+        }
+    }
+
+    /// Whether the format is plain text that cannot be identified by magic bytes
+    pub fn is_text(&self) -> bool {
+        matches!(self, FileType::Txt | FileType::Html | FileType::Markdown)
+    }
+
+    /// Display name of the format
+    pub fn name(&self) -> &'static str {
+        match self {
+            FileType::Pdf => "PDF",
+            FileType::Docx => "DOCX",
+            FileType::Txt => "TXT",
+            FileType::Html => "HTML",
+            FileType::Markdown => "Markdown",
+            FileType::Odt => "ODT",
+            FileType::Rtf => "RTF",
+            FileType::Pptx => "PPTX",
+            FileType::Odp => "ODP",
+            FileType::Zip => "ZIP",
+        }
+    }
+}
+
+/// Options controlling how markup formats are converted to plain text
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExtractionOptions {
+    /// Drop code blocks (Markdown code fences, HTML `<pre>`) instead of
+    /// treating their content as prose
+    pub strip_code: bool,
+}
+
+impl Default for ExtractionOptions {
+    fn default() -> Self {
+        Self { strip_code: true }
+    }
 }
 
 /// Extract text from file bytes based on file type
 pub fn extract_text(file_bytes: &[u8], file_type: FileType) -> Result<String, String> {
+    extract_text_with_options(file_bytes, file_type, &ExtractionOptions::default())
+}
+
+/// Extract text from file bytes based on file type, using custom extraction options
+pub fn extract_text_with_options(
+    file_bytes: &[u8],
+    file_type: FileType,
+    options: &ExtractionOptions,
+) -> Result<String, String> {
     match file_type {
         FileType::Pdf => extract_pdf(file_bytes),
         FileType::Docx => extract_docx(file_bytes),
         FileType::Txt => extract_txt(file_bytes),
+        FileType::Html => extract_html(file_bytes, options),
+        FileType::Markdown => extract_markdown(file_bytes, options),
+        FileType::Odt => extract_odt(file_bytes),
+        FileType::Rtf => extract_rtf(file_bytes),
+        FileType::Pptx => extract_pptx(file_bytes),
+        FileType::Odp => extract_odp(file_bytes),
+        FileType::Zip => Err("ZIP archives must be expanded before text extraction".to_string()),
     }
 }
@@ -0,0 +1,12 @@
+//! ODP (OpenDocument Presentation) extraction module
+
+use super::odt::extract_odf_paragraphs;
+
+/// Extract text from ODP file bytes
+///
+/// Reads the slide text from `content.xml`. Speaker notes are skipped.
+/// Slide items are usually not terminated by punctuation, so every
+/// paragraph is separated by a blank line to keep it a separate sentence.
+pub fn extract_odp(file_bytes: &[u8]) -> Result<String, String> {
+    extract_odf_paragraphs(file_bytes, "ODP").map(|paragraphs| paragraphs.join("\n\n"))
+}
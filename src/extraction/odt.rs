@@ -0,0 +1,48 @@
+//! ODT (OpenDocument Text) extraction module
+
+use xml::attribute::OwnedAttribute;
+
+use super::package::{collect_paragraphs, open_package, read_part, ElementRole};
+
+/// Upper bound on the `text:c` count of `<text:s>`; runs of spaces collapse
+/// to one anyway, and an unbounded count would allocate without limit
+const MAX_SPACES: usize = 1024;
+
+/// Extract text from ODT file bytes
+///
+/// Reads `content.xml` from the ZIP package and returns the text of every
+/// paragraph and heading, one per line. Annotations, footnotes and tracked
+/// deletions are skipped.
+pub fn extract_odt(file_bytes: &[u8]) -> Result<String, String> {
+    extract_odf_paragraphs(file_bytes, "ODT").map(|paragraphs| paragraphs.join("\n"))
+}
+
+/// Extract paragraph texts from the `content.xml` part of an OpenDocument package
+pub(crate) fn extract_odf_paragraphs(file_bytes: &[u8], format: &str) -> Result<Vec<String>, String> {
+    let mut archive = open_package(file_bytes, format)?;
+    let content = read_part(&mut archive, "content.xml", format)?;
+
+    collect_paragraphs(&content, format, classify_odf_element)
+}
+
+/// Role of an OpenDocument element in text extraction
+fn classify_odf_element(prefix: Option<&str>, local_name: &str, attributes: &[OwnedAttribute]) -> ElementRole {
+    match (prefix, local_name) {
+        (Some("text"), "p") | (Some("text"), "h") => ElementRole::Paragraph,
+        (Some("text"), "s") => {
+            let count = attributes
+                .iter()
+                .find(|attr| attr.name.local_name == "c")
+                .and_then(|attr| attr.value.parse::<usize>().ok())
+                .unwrap_or(1)
+                .min(MAX_SPACES);
+            ElementRole::Spaces(count)
+        }
+        (Some("text"), "tab") | (Some("text"), "line-break") => ElementRole::Spaces(1),
+        (Some("office"), "annotation")
+        | (Some("text"), "note")
+        | (Some("text"), "tracked-changes")
+        | (Some("presentation"), "notes") => ElementRole::Skip,
+        _ => ElementRole::Inline,
+    }
+}
@@ -0,0 +1,116 @@
+//! Helpers for ZIP-based document packages (OpenDocument, Office Open XML)
+
+use std::io::{Cursor, Read};
+
+use xml::attribute::OwnedAttribute;
+use xml::reader::{EventReader, XmlEvent};
+use zip::ZipArchive;
+
+/// A ZIP package read from memory
+pub(crate) type Package<'a> = ZipArchive<Cursor<&'a [u8]>>;
+
+/// Open ZIP package bytes as an archive
+pub(crate) fn open_package<'a>(file_bytes: &'a [u8], format: &str) -> Result<Package<'a>, String> {
+    ZipArchive::new(Cursor::new(file_bytes))
+        .map_err(|e| format!("Failed to extract {}: invalid package: {}", format, e))
+}
+
+/// Read a single XML part from a ZIP package as a string
+pub(crate) fn read_part(
+    archive: &mut Package<'_>,
+    name: &str,
+    format: &str,
+) -> Result<String, String> {
+    let mut part = archive
+        .by_name(name)
+        .map_err(|e| format!("Failed to extract {}: missing '{}': {}", format, name, e))?;
+
+    let mut content = String::new();
+    part.read_to_string(&mut content)
+        .map_err(|e| format!("Failed to extract {}: cannot read '{}': {}", format, name, e))?;
+
+    Ok(content)
+}
+
+/// How an XML element contributes to the extracted text
+pub(crate) enum ElementRole {
+    /// Starts and ends a paragraph
+    Paragraph,
+    /// Inserts `n` spaces (tabs, line breaks, `text:s`)
+    Spaces(usize),
+    /// Element and everything inside it is ignored
+    Skip,
+    /// Text content is kept, no structural meaning
+    Inline,
+}
+
+/// Collect paragraph texts from an XML part
+///
+/// `classify` decides the role of every element from its namespace prefix,
+/// local name and attributes. Character data is only collected inside
+/// paragraphs, and empty paragraphs are dropped.
+pub(crate) fn collect_paragraphs<F>(xml: &str, format: &str, classify: F) -> Result<Vec<String>, String>
+where
+    F: Fn(Option<&str>, &str, &[OwnedAttribute]) -> ElementRole,
+{
+    let mut paragraphs = Vec::new();
+    let mut current = String::new();
+    let mut paragraph_depth = 0usize;
+    let mut skip_depth = 0usize;
+
+    for event in EventReader::from_str(xml) {
+        let event = event.map_err(|e| format!("Failed to extract {}: malformed XML: {}", format, e))?;
+
+        match event {
+            XmlEvent::StartElement { name, attributes, .. } => {
+                if skip_depth > 0 {
+                    skip_depth += 1;
+                    continue;
+                }
+                match classify(name.prefix.as_deref(), &name.local_name, &attributes) {
+                    ElementRole::Paragraph => {
+                        if paragraph_depth == 0 {
+                            current.clear();
+                        }
+                        paragraph_depth += 1;
+                    }
+                    ElementRole::Spaces(count) if paragraph_depth > 0 => {
+                        current.extend(std::iter::repeat_n(' ', count));
+                    }
+                    ElementRole::Skip => skip_depth = 1,
+                    _ => {}
+                }
+            }
+            XmlEvent::EndElement { name } => {
+                if skip_depth > 0 {
+                    skip_depth -= 1;
+                    continue;
+                }
+                let role = classify(name.prefix.as_deref(), &name.local_name, &[]);
+                if matches!(role, ElementRole::Paragraph) && paragraph_depth > 0 {
+                    paragraph_depth -= 1;
+                    if paragraph_depth == 0 {
+                        let text = current.split_whitespace().collect::<Vec<_>>().join(" ");
+                        if !text.is_empty() {
+                            paragraphs.push(text);
+                        }
+                        current.clear();
+                    }
+                }
+            }
+            XmlEvent::Characters(text) | XmlEvent::CData(text)
+                if skip_depth == 0 && paragraph_depth > 0 =>
+            {
+                current.push_str(&text);
+            }
+            XmlEvent::Whitespace(text)
+                if skip_depth == 0 && paragraph_depth > 0 && !current.is_empty() =>
+            {
+                current.push_str(&text);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(paragraphs)
+}
@@ -0,0 +1,50 @@
+//! PPTX (PowerPoint Open XML) extraction module
+
+use xml::attribute::OwnedAttribute;
+
+use super::package::{collect_paragraphs, open_package, read_part, ElementRole};
+
+/// Extract text from PPTX file bytes
+///
+/// Reads `ppt/slides/slideN.xml` parts in slide order. Speaker notes live
+/// in separate parts and are not included. Slide items are usually not
+/// terminated by punctuation, so every paragraph is separated by a blank
+/// line to keep it a separate sentence.
+pub fn extract_pptx(file_bytes: &[u8]) -> Result<String, String> {
+    let mut archive = open_package(file_bytes, "PPTX")?;
+
+    // Slide parts sorted by their number (slide10 after slide9)
+    let mut slides: Vec<(u32, String)> = archive
+        .file_names()
+        .filter_map(|name| {
+            name.strip_prefix("ppt/slides/slide")
+                .and_then(|rest| rest.strip_suffix(".xml"))
+                .and_then(|number| number.parse().ok())
+                .map(|number| (number, name.to_string()))
+        })
+        .collect();
+    slides.sort();
+
+    if slides.is_empty() {
+        return Err("Failed to extract PPTX: presentation contains no slides".to_string());
+    }
+
+    let paragraphs = slides
+        .iter()
+        .map(|(_, name)| {
+            let xml = read_part(&mut archive, name, "PPTX")?;
+            collect_paragraphs(&xml, "PPTX", classify_drawingml_element)
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(paragraphs.concat().join("\n\n"))
+}
+
+/// Role of a DrawingML element in text extraction
+fn classify_drawingml_element(prefix: Option<&str>, local_name: &str, _: &[OwnedAttribute]) -> ElementRole {
+    match (prefix, local_name) {
+        (Some("a"), "p") => ElementRole::Paragraph,
+        (Some("a"), "br") | (Some("a"), "tab") => ElementRole::Spaces(1),
+        _ => ElementRole::Inline,
+    }
+}
@@ -0,0 +1,197 @@
+//! RTF (Rich Text Format) extraction module
+
+/// Destinations whose content is not part of the document text
+const SKIPPED_DESTINATIONS: &[&str] = &[
+    "fonttbl", "colortbl", "stylesheet", "listtable", "listoverridetable", "info", "pict",
+    "object", "objdata", "header", "headerl", "headerr", "headerf", "footer", "footerl",
+    "footerr", "footerf", "footnote", "annotation", "fldinst", "datastore", "themedata",
+    "colorschememapping", "latentstyles", "rsidtbl", "generator", "xmlnstbl", "mmathPr",
+    "filetbl", "revtbl", "pgdsctbl", "bkmkstart", "bkmkend", "shpinst", "nonshppict",
+];
+
+/// Parser state of a single `{ ... }` group
+#[derive(Debug, Clone, Copy)]
+struct GroupState {
+    /// Group content is not document text
+    skip: bool,
+    /// Number of fallback characters following a `\uN` control word
+    unicode_skip: usize,
+}
+
+/// Extract text from RTF file bytes
+///
+/// Implements a small control-word parser: groups, ignorable destinations
+/// (font tables, pictures, metadata, ...), `\par`/`\line` breaks, hex
+/// escapes (`\'e9`, decoded as Windows-1252) and Unicode escapes (`\u8217?`).
+pub fn extract_rtf(file_bytes: &[u8]) -> Result<String, String> {
+    if !file_bytes.starts_with(b"{\\rtf") {
+        return Err("Failed to extract RTF: missing '{\\rtf' header".to_string());
+    }
+
+    let mut text = String::new();
+    let mut stack: Vec<GroupState> = Vec::new();
+    let mut state = GroupState { skip: false, unicode_skip: 1 };
+    // Fallback characters still to be skipped after a `\uN`
+    let mut pending_skip = 0usize;
+    let mut i = 0usize;
+
+    while i < file_bytes.len() {
+        let byte = file_bytes[i];
+        match byte {
+            b'{' => {
+                stack.push(state);
+                pending_skip = 0;
+                i += 1;
+            }
+            b'}' => {
+                state = stack.pop().unwrap_or(state);
+                pending_skip = 0;
+                i += 1;
+            }
+            b'\\' => {
+                let (control, next) = read_control(file_bytes, i + 1);
+                i = next;
+
+                // Fallback representation of a preceding `\uN`
+                if pending_skip > 0 && matches!(control, Control::Hex(_) | Control::Symbol(_)) {
+                    pending_skip -= 1;
+                    continue;
+                }
+                pending_skip = 0;
+
+                match control {
+                    Control::Symbol(b'*') => state.skip = true,
+                    Control::Symbol(c @ (b'\\' | b'{' | b'}')) => push(&mut text, &state, c as char),
+                    Control::Symbol(b'~') => push(&mut text, &state, ' '),
+                    Control::Symbol(b'_') => push(&mut text, &state, '-'),
+                    Control::Symbol(_) => {}
+                    Control::Hex(value) => push(&mut text, &state, decode_cp1252(value)),
+                    Control::Word(word, param) => {
+                        if SKIPPED_DESTINATIONS.contains(&word.as_str()) {
+                            state.skip = true;
+                            continue;
+                        }
+                        match word.as_str() {
+                            "par" | "line" | "sect" | "page" | "row" => push(&mut text, &state, '\n'),
+                            "tab" | "cell" => push(&mut text, &state, ' '),
+                            "uc" => state.unicode_skip = param.unwrap_or(1).max(0) as usize,
+                            "u" => {
+                                if let Some(code) = param {
+                                    // Negative values encode code points above 32767
+                                    let code = if code < 0 { code + 65536 } else { code };
+                                    if let Some(c) = char::from_u32(code as u32) {
+                                        push(&mut text, &state, c);
+                                    }
+                                    pending_skip = state.unicode_skip;
+                                }
+                            }
+                            "emdash" => push(&mut text, &state, '—'),
+                            "endash" => push(&mut text, &state, '–'),
+                            "lquote" => push(&mut text, &state, '‘'),
+                            "rquote" => push(&mut text, &state, '’'),
+                            "ldblquote" => push(&mut text, &state, '“'),
+                            "rdblquote" => push(&mut text, &state, '”'),
+                            "bullet" => push(&mut text, &state, '•'),
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            b'\r' | b'\n' => i += 1,
+            _ => {
+                i += 1;
+                if pending_skip > 0 {
+                    pending_skip -= 1;
+                    continue;
+                }
+                push(&mut text, &state, decode_cp1252(byte));
+            }
+        }
+    }
+
+    let paragraphs: Vec<String> = text
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    Ok(paragraphs.join("\n"))
+}
+
+/// A parsed control sequence
+enum Control {
+    /// Control word with optional numeric parameter (`\par`, `\fs24`)
+    Word(String, Option<i32>),
+    /// Hex-encoded byte (`\'e9`)
+    Hex(u8),
+    /// Control symbol (`\*`, `\~`, `\{`)
+    Symbol(u8),
+}
+
+/// Read a control sequence starting right after the backslash
+///
+/// Returns the control and the index of the first byte after it.
+fn read_control(bytes: &[u8], start: usize) -> (Control, usize) {
+    let Some(&first) = bytes.get(start) else {
+        return (Control::Symbol(b'\\'), start);
+    };
+
+    if first == b'\'' {
+        let hex = bytes
+            .get(start + 1..start + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        return match hex {
+            Some(value) => (Control::Hex(value), start + 3),
+            None => (Control::Symbol(b'\''), start + 1),
+        };
+    }
+
+    if !first.is_ascii_alphabetic() {
+        return (Control::Symbol(first), start + 1);
+    }
+
+    let word_end = bytes[start..]
+        .iter()
+        .position(|b| !b.is_ascii_alphabetic())
+        .map_or(bytes.len(), |offset| start + offset);
+    let word = String::from_utf8_lossy(&bytes[start..word_end]).into_owned();
+
+    let mut end = word_end;
+    if bytes.get(end) == Some(&b'-') {
+        end += 1;
+    }
+    while bytes.get(end).is_some_and(|b| b.is_ascii_digit()) {
+        end += 1;
+    }
+    let param = std::str::from_utf8(&bytes[word_end..end])
+        .ok()
+        .and_then(|p| p.parse::<i32>().ok());
+
+    // A single space delimits the control word and is not part of the text
+    if bytes.get(end) == Some(&b' ') {
+        end += 1;
+    }
+
+    (Control::Word(word, param), end)
+}
+
+/// Append a character unless the current group is skipped
+fn push(text: &mut String, state: &GroupState, c: char) {
+    if !state.skip {
+        text.push(c);
+    }
+}
+
+/// Decode a single Windows-1252 byte
+fn decode_cp1252(byte: u8) -> char {
+    const HIGH: [char; 32] = [
+        '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8d}', 'Ž',
+        '\u{8f}', '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9d}',
+        'ž', 'Ÿ',
+    ];
+    match byte {
+        0x80..=0x9f => HIGH[(byte - 0x80) as usize],
+        _ => byte as char,
+    }
+}
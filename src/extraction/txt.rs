@@ -2,10 +2,36 @@
 
 /// Extract text from TXT file bytes
 ///
-/// Simply converts bytes to UTF-8 string.
+/// Decodes UTF-8 (with or without BOM) and BOM-prefixed UTF-16.
 /// Returns the decoded text content.
 pub fn extract_txt(file_bytes: &[u8]) -> Result<String, String> {
-    String::from_utf8(file_bytes.to_vec())
-        .map_err(|e| format!("Failed to decode TXT as UTF-8: {}", e))
-        .map(|text| text.trim().to_string())
+    decode_text(file_bytes, "TXT").map(|text| text.trim().to_string())
+}
+
+/// Decode text file bytes, honoring a UTF-8 or UTF-16 byte order mark
+pub(crate) fn decode_text(file_bytes: &[u8], format: &str) -> Result<String, String> {
+    if let Some(utf8) = file_bytes.strip_prefix(b"\xEF\xBB\xBF") {
+        return decode_utf8(utf8, format);
+    }
+    if let Some(utf16) = file_bytes.strip_prefix(b"\xFF\xFE") {
+        return decode_utf16(utf16, u16::from_le_bytes, format);
+    }
+    if let Some(utf16) = file_bytes.strip_prefix(b"\xFE\xFF") {
+        return decode_utf16(utf16, u16::from_be_bytes, format);
+    }
+    decode_utf8(file_bytes, format)
+}
+
+fn decode_utf8(bytes: &[u8], format: &str) -> Result<String, String> {
+    String::from_utf8(bytes.to_vec())
+        .map_err(|e| format!("Failed to decode {} as UTF-8: {}", format, e))
+}
+
+fn decode_utf16(bytes: &[u8], to_unit: fn([u8; 2]) -> u16, format: &str) -> Result<String, String> {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| to_unit([pair[0], pair[1]]))
+        .collect();
+
+    String::from_utf16(&units).map_err(|e| format!("Failed to decode {} as UTF-16: {}", format, e))
 }
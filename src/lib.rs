@@ -7,11 +7,13 @@
 //! - `api` - HTTP API handlers and server configuration
 //! - `core` - Pure functions for text processing and similarity computation
 //! - `models` - Immutable data structures
-//! - `extraction` - File extraction modules (PDF, DOCX, TXT)
+//! - `extraction` - File extraction modules (PDF, DOCX, ODT, RTF, PPTX, ODP, TXT, HTML, Markdown)
 //! - `sentence` - Sentence splitting utilities
+//! - `report` - Human-readable reports (HTML) of analysis results
 
 pub mod api;
 pub mod core;
 pub mod models;
 pub mod extraction;
 pub mod sentence;
+pub mod report;
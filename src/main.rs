@@ -2,6 +2,11 @@
 //!
 //! A backend service for analyzing document similarity using TF-IDF
 //! and Cosine Similarity with parallel processing.
+//!
+//! Without arguments the HTTP server is started; `report FILE...` writes an
+//! HTML comparison report of local files instead.
+
+mod cli;
 
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -9,6 +14,12 @@ use document_similarity_analyzer::api::run_server;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    // Offline report generation
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("report") {
+        return cli::run_report(&args[1..]);
+    }
+
     // Initialize tracing/logging
     tracing_subscriber::registry()
         .with(
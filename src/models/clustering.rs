@@ -0,0 +1,84 @@
+//! Models for grouping documents by similarity
+
+use serde::{Deserialize, Serialize};
+
+/// How documents are grouped from their pairwise similarity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClusterMethod {
+    /// Agglomerative clustering; cluster distance is the closest pair of members
+    Single,
+    /// Agglomerative clustering; cluster distance is the mean over all member pairs
+    #[default]
+    Average,
+    /// Agglomerative clustering; cluster distance is the farthest pair of members
+    Complete,
+    /// Connected components of the graph of pairs at or above the threshold
+    Components,
+}
+
+impl ClusterMethod {
+    /// Parse a method name: `single`, `average`, `complete` or `components`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().replace('-', "_").as_str() {
+            "single" => Some(ClusterMethod::Single),
+            "average" | "upgma" => Some(ClusterMethod::Average),
+            "complete" => Some(ClusterMethod::Complete),
+            "components" | "connected_components" | "threshold" => Some(ClusterMethod::Components),
+            _ => None,
+        }
+    }
+
+    /// Name as used in requests and responses
+    pub fn name(&self) -> &'static str {
+        match self {
+            ClusterMethod::Single => "single",
+            ClusterMethod::Average => "average",
+            ClusterMethod::Complete => "complete",
+            ClusterMethod::Components => "components",
+        }
+    }
+}
+
+/// One step of agglomerative clustering
+///
+/// Leaves are numbered `0..n` in document order; the cluster created by the
+/// `k`-th merge is node `n + k`, as in SciPy's linkage matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DendrogramMerge {
+    /// Node id of the first merged cluster (the smaller id)
+    pub left: usize,
+    /// Node id of the second merged cluster
+    pub right: usize,
+    /// Linkage similarity of the two clusters
+    pub similarity: f32,
+    /// Merge height as a distance, `1 - similarity`
+    pub height: f32,
+    /// Number of documents in the merged cluster
+    pub size: usize,
+}
+
+/// A group of two or more documents
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocumentCluster {
+    /// Cluster id, as used in `assignments`
+    pub id: usize,
+    /// Filenames of the member documents, in document order
+    pub documents: Vec<String>,
+}
+
+/// Grouping of the analyzed documents
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocumentClustering {
+    /// Method used
+    pub method: ClusterMethod,
+    /// Minimum similarity for documents to end up in the same cluster
+    pub threshold: f32,
+    /// Cluster id of every document, in document order; ids are numbered by first member
+    pub assignments: Vec<usize>,
+    /// Clusters with at least two documents
+    pub clusters: Vec<DocumentCluster>,
+    /// Full merge history (empty for `components`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dendrogram: Vec<DendrogramMerge>,
+}
@@ -1,10 +1,12 @@
 //! Immutable data models for document similarity analysis
 
+mod clustering;
 mod document;
 mod request;
 mod response;
 mod sentence_analysis;
 
+pub use clustering::*;
 pub use document::*;
 pub use request::*;
 pub use response::*;
@@ -1,6 +1,9 @@
 //! Models for sentence-level document analysis
 
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+use crate::models::{DocumentClustering, SimilarityMatrix};
 
 /// Metadata for analysis results
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,6 +16,22 @@ pub struct AnalysisMetadata {
     pub processing_time_ms: u64,
     /// Similarity threshold used for filtering
     pub threshold: f32,
+    /// Sentences excluded from matching because they are too short
+    #[serde(default)]
+    pub filtered_sentences: usize,
+    /// Sentences detected as quoted or cited (0 when detection is off)
+    #[serde(default)]
+    pub quoted_sentences: usize,
+    /// Sentences in trailing reference sections excluded from scoring
+    #[serde(default)]
+    pub reference_sentences: usize,
+    /// Matches available for paging: sentence pairs at or above the threshold
+    /// (after alignment and `per_document_pair_limit`), including those outside the returned page
+    #[serde(default)]
+    pub total_matches: usize,
+    /// Non-fatal issues found while processing (e.g. extension/content mismatches)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
 }
 
 impl AnalysisMetadata {
@@ -27,8 +46,80 @@ impl AnalysisMetadata {
             total_sentences,
             processing_time_ms,
             threshold,
+            filtered_sentences: 0,
+            quoted_sentences: 0,
+            reference_sentences: 0,
+            total_matches: 0,
+            warnings: Vec::new(),
         }
     }
+
+    /// Record the number of sentences excluded from matching
+    pub fn with_filtered_sentences(self, filtered_sentences: usize) -> Self {
+        Self { filtered_sentences, ..self }
+    }
+
+    /// Record the number of quoted or cited sentences
+    pub fn with_quoted_sentences(self, quoted_sentences: usize) -> Self {
+        Self { quoted_sentences, ..self }
+    }
+
+    /// Record the number of sentences in excluded reference sections
+    pub fn with_reference_sentences(self, reference_sentences: usize) -> Self {
+        Self { reference_sentences, ..self }
+    }
+
+    /// Record the number of matches available for paging
+    pub fn with_total_matches(self, total_matches: usize) -> Self {
+        Self { total_matches, ..self }
+    }
+
+    /// Attach processing warnings
+    pub fn with_warnings(self, warnings: Vec<String>) -> Self {
+        Self { warnings, ..self }
+    }
+}
+
+/// Per-document sentence statistics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentSummary {
+    /// Document filename
+    pub filename: String,
+    /// Number of sentences in the document
+    pub total_sentences: usize,
+    /// Sentences removed because they match the assignment template
+    #[serde(default)]
+    pub template_sentences: usize,
+}
+
+impl DocumentSummary {
+    pub fn new(filename: String, total_sentences: usize) -> Self {
+        Self {
+            filename,
+            total_sentences,
+            template_sentences: 0,
+        }
+    }
+
+    /// Record the number of sentences removed as template text
+    pub fn with_template_sentences(self, template_sentences: usize) -> Self {
+        Self { template_sentences, ..self }
+    }
+}
+
+/// A sentence shared by a large fraction of the documents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoilerplateSentence {
+    /// The sentence text (first occurrence)
+    pub sentence: String,
+    /// Number of documents containing the sentence
+    pub documents: usize,
+}
+
+impl BoilerplateSentence {
+    pub fn new(sentence: String, documents: usize) -> Self {
+        Self { sentence, documents }
+    }
 }
 
 /// A single sentence similarity match
@@ -48,6 +139,18 @@ pub struct SentenceMatch {
     pub target_sentence: String,
     /// Similarity score (0.0 to 1.0)
     pub similarity: f32,
+    /// Either sentence lies inside quotation marks (quotation detection enabled)
+    #[serde(default)]
+    pub quoted: bool,
+    /// Either sentence contains an in-text citation (quotation detection enabled)
+    #[serde(default)]
+    pub cited: bool,
+    /// Position of the source document in the upload
+    #[serde(default)]
+    pub source_doc_index: usize,
+    /// Position of the target document in the upload
+    #[serde(default)]
+    pub target_doc_index: usize,
 }
 
 impl SentenceMatch {
@@ -68,8 +171,76 @@ impl SentenceMatch {
             target_sentence_index,
             target_sentence,
             similarity,
+            quoted: false,
+            cited: false,
+            source_doc_index: 0,
+            target_doc_index: 0,
         }
     }
+
+    /// Record the positions of both documents, which tell apart documents
+    /// with the same filename
+    pub fn with_doc_indices(self, source_doc_index: usize, target_doc_index: usize) -> Self {
+        Self {
+            source_doc_index,
+            target_doc_index,
+            ..self
+        }
+    }
+
+    /// Record whether the matched sentences are quoted or cited
+    pub fn with_attribution(self, quoted: bool, cited: bool) -> Self {
+        Self { quoted, cited, ..self }
+    }
+}
+
+/// How sentence similarities are aggregated into a document-pair score
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregationStrategy {
+    /// Mean similarity over all sentence pairs
+    #[default]
+    Mean,
+    /// Mean of every sentence's best similarity in the other document
+    BestMatch,
+    /// Score of a greedy one-to-one sentence alignment
+    Alignment,
+    /// Cosine similarity of whole-document TF-IDF vectors
+    Document,
+}
+
+impl AggregationStrategy {
+    /// Parse a strategy name: `mean`, `best_match`, `alignment` or `document`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().replace('-', "_").as_str() {
+            "mean" => Some(AggregationStrategy::Mean),
+            "best_match" | "max" => Some(AggregationStrategy::BestMatch),
+            "alignment" | "greedy" => Some(AggregationStrategy::Alignment),
+            "document" | "tfidf" => Some(AggregationStrategy::Document),
+            _ => None,
+        }
+    }
+
+    /// Name as used in requests and responses
+    pub fn name(&self) -> &'static str {
+        match self {
+            AggregationStrategy::Mean => "mean",
+            AggregationStrategy::BestMatch => "best_match",
+            AggregationStrategy::Alignment => "alignment",
+            AggregationStrategy::Document => "document",
+        }
+    }
+}
+
+/// How much of one document is found in another
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct DirectionalCoverage {
+    /// Share of the document's sentences with a match (≥ threshold) in the other document
+    pub sentences: f32,
+    /// Mean over the document's sentences of their best similarity in the other document
+    pub best_match_mean: f32,
+    /// Share of the document's tokens that lie in matched sentences
+    pub tokens: f32,
 }
 
 /// Global similarity between two documents
@@ -83,11 +254,59 @@ pub struct GlobalSimilarity {
     pub doc_b: String,
     /// Overall similarity score (0.0 to 1.0)
     pub score: f32,
+    /// Aggregation strategy that produced `score`
+    #[serde(default)]
+    pub strategy: AggregationStrategy,
+    /// How much of docA is found in docB
+    #[serde(rename = "coverageA", default)]
+    pub coverage_a: DirectionalCoverage,
+    /// How much of docB is found in docA
+    #[serde(rename = "coverageB", default)]
+    pub coverage_b: DirectionalCoverage,
+    /// Position of the first document in the upload
+    #[serde(rename = "docAIndex", default)]
+    pub doc_a_index: usize,
+    /// Position of the second document in the upload
+    #[serde(rename = "docBIndex", default)]
+    pub doc_b_index: usize,
 }
 
 impl GlobalSimilarity {
     pub fn new(doc_a: String, doc_b: String, score: f32) -> Self {
-        Self { doc_a, doc_b, score }
+        Self {
+            doc_a,
+            doc_b,
+            score,
+            strategy: AggregationStrategy::Mean,
+            coverage_a: DirectionalCoverage::default(),
+            coverage_b: DirectionalCoverage::default(),
+            doc_a_index: 0,
+            doc_b_index: 0,
+        }
+    }
+
+    /// Record the positions of both documents, which tell apart documents
+    /// with the same filename
+    pub fn with_doc_indices(self, doc_a_index: usize, doc_b_index: usize) -> Self {
+        Self {
+            doc_a_index,
+            doc_b_index,
+            ..self
+        }
+    }
+
+    /// Record the aggregation strategy that produced the score
+    pub fn with_strategy(self, strategy: AggregationStrategy) -> Self {
+        Self { strategy, ..self }
+    }
+
+    /// Attach the directional coverage of both documents
+    pub fn with_coverage(self, coverage_a: DirectionalCoverage, coverage_b: DirectionalCoverage) -> Self {
+        Self {
+            coverage_a,
+            coverage_b,
+            ..self
+        }
     }
 }
 
@@ -100,6 +319,24 @@ pub struct SentenceAnalysisResponse {
     pub matches: Vec<SentenceMatch>,
     /// Global similarity scores between document pairs
     pub global_similarity: Vec<GlobalSimilarity>,
+    /// Per-document sentence statistics
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub documents: Vec<DocumentSummary>,
+    /// Sentences suppressed as common boilerplate instead of reported as matches
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub common_boilerplate: Vec<BoilerplateSentence>,
+    /// Whole-document TF-IDF cosine of every document pair, when requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document_similarity_matrix: Option<SimilarityMatrix>,
+    /// Aggregated sentence similarity of every document pair, when requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sentence_similarity_matrix: Option<SimilarityMatrix>,
+    /// Groups of similar documents, when requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clustering: Option<DocumentClustering>,
+    /// Cursor of the next page of matches, when `matches` was cut off by a limit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
 
 impl SentenceAnalysisResponse {
@@ -112,6 +349,142 @@ impl SentenceAnalysisResponse {
             metadata,
             matches,
             global_similarity,
+            documents: Vec::new(),
+            common_boilerplate: Vec::new(),
+            document_similarity_matrix: None,
+            sentence_similarity_matrix: None,
+            clustering: None,
+            next_cursor: None,
+        }
+    }
+
+    /// Attach the NxN document-level and sentence-aggregation matrices
+    pub fn with_similarity_matrices(
+        self,
+        document_similarity_matrix: Option<SimilarityMatrix>,
+        sentence_similarity_matrix: Option<SimilarityMatrix>,
+    ) -> Self {
+        Self {
+            document_similarity_matrix,
+            sentence_similarity_matrix,
+            ..self
+        }
+    }
+
+    /// Attach the document clustering
+    pub fn with_clustering(self, clustering: Option<DocumentClustering>) -> Self {
+        Self { clustering, ..self }
+    }
+
+    /// Attach the cursor of the next page of matches
+    pub fn with_next_cursor(self, next_cursor: Option<String>) -> Self {
+        Self { next_cursor, ..self }
+    }
+
+    /// Attach the sentences suppressed as common boilerplate
+    pub fn with_common_boilerplate(self, common_boilerplate: Vec<BoilerplateSentence>) -> Self {
+        Self { common_boilerplate, ..self }
+    }
+
+    /// Attach per-document statistics
+    pub fn with_documents(self, documents: Vec<DocumentSummary>) -> Self {
+        Self { documents, ..self }
+    }
+}
+
+/// One line of a streamed (NDJSON) analysis
+///
+/// Every match is sent as soon as it is found, followed by a single summary
+/// whose `matches` list is empty, or by an error if the analysis failed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnalysisStreamRecord {
+    Match(SentenceMatch),
+    Summary(Box<SentenceAnalysisResponse>),
+    Error { message: String },
+}
+
+/// Order similarities from highest to lowest, with NaN after every number
+///
+/// A total order, so sorting with it never panics and always gives the same
+/// result; break ties with indices for a fully deterministic order.
+pub fn descending_similarity(a: f32, b: f32) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => b.total_cmp(&a),
+    }
+}
+
+/// Position of a match in the result order, used as a keyset cursor
+///
+/// Matches are ordered by similarity descending, then by source document,
+/// source sentence, target document and target sentence ascending (documents
+/// by upload index), so the order is total and a cursor names exactly one
+/// position. Cursors are only meaningful for the same files and options.
+#[derive(Debug, Clone, Copy)]
+pub struct MatchCursor {
+    pub similarity: f32,
+    pub source_doc: usize,
+    pub source_sentence_index: usize,
+    pub target_doc: usize,
+    pub target_sentence_index: usize,
+}
+
+impl MatchCursor {
+    /// Opaque string form, e.g. `3f733333-0-4-1-7`
+    pub fn encode(&self) -> String {
+        format!(
+            "{:08x}-{}-{}-{}-{}",
+            self.similarity.to_bits(),
+            self.source_doc,
+            self.source_sentence_index,
+            self.target_doc,
+            self.target_sentence_index
+        )
+    }
+
+    /// Parse a cursor produced by [`MatchCursor::encode`]
+    pub fn decode(cursor: &str) -> Option<Self> {
+        let mut parts = cursor.trim().split('-');
+        let similarity = f32::from_bits(u32::from_str_radix(parts.next()?, 16).ok()?);
+        let mut index = || parts.next()?.parse::<usize>().ok();
+        let cursor = MatchCursor {
+            similarity,
+            source_doc: index()?,
+            source_sentence_index: index()?,
+            target_doc: index()?,
+            target_sentence_index: index()?,
+        };
+
+        if parts.next().is_some() || similarity.is_nan() {
+            return None;
         }
+        Some(cursor)
+    }
+}
+
+impl PartialEq for MatchCursor {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for MatchCursor {}
+
+impl PartialOrd for MatchCursor {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for MatchCursor {
+    fn cmp(&self, other: &Self) -> Ordering {
+        descending_similarity(self.similarity, other.similarity)
+            .then(self.source_doc.cmp(&other.source_doc))
+            .then(self.source_sentence_index.cmp(&other.source_sentence_index))
+            .then(self.target_doc.cmp(&other.target_doc))
+            .then(self.target_sentence_index.cmp(&other.target_sentence_index))
     }
 }
@@ -0,0 +1,196 @@
+//! Spreadsheet and BI exports: CSV, JSON Lines and a pairwise score matrix
+//!
+//! CSV output starts with a UTF-8 byte order mark and uses CRLF line endings
+//! so that Excel opens it with the right encoding. Text cells starting with
+//! `=`, `+`, `-` or `@` are prefixed with `'` so spreadsheets do not
+//! evaluate them as formulas.
+
+use std::io::{self, Write};
+
+use crate::models::{DirectionalCoverage, SentenceAnalysisResponse};
+
+const BOM: &str = "\u{feff}";
+
+/// Write every match as a CSV row
+pub fn write_matches_csv<W: Write>(response: &SentenceAnalysisResponse, mut out: W) -> io::Result<()> {
+    write!(out, "{}", BOM)?;
+    write_row(
+        &mut out,
+        &[
+            "source_doc",
+            "source_sentence_index",
+            "source_sentence",
+            "target_doc",
+            "target_sentence_index",
+            "target_sentence",
+            "similarity",
+            "quoted",
+            "cited",
+        ],
+    )?;
+
+    for m in &response.matches {
+        write_row(
+            &mut out,
+            &[
+                &text_cell(&m.source_doc),
+                &m.source_sentence_index.to_string(),
+                &text_cell(&m.source_sentence),
+                &text_cell(&m.target_doc),
+                &m.target_sentence_index.to_string(),
+                &text_cell(&m.target_sentence),
+                &m.similarity.to_string(),
+                &m.quoted.to_string(),
+                &m.cited.to_string(),
+            ],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Write every document pair's global similarity as a CSV row
+pub fn write_global_similarity_csv<W: Write>(response: &SentenceAnalysisResponse, mut out: W) -> io::Result<()> {
+    write!(out, "{}", BOM)?;
+    write_row(
+        &mut out,
+        &[
+            "doc_a",
+            "doc_b",
+            "score",
+            "strategy",
+            "coverage_a_sentences",
+            "coverage_a_best_match_mean",
+            "coverage_a_tokens",
+            "coverage_b_sentences",
+            "coverage_b_best_match_mean",
+            "coverage_b_tokens",
+        ],
+    )?;
+
+    for global in &response.global_similarity {
+        let [a_sentences, a_best, a_tokens] = coverage_cells(&global.coverage_a);
+        let [b_sentences, b_best, b_tokens] = coverage_cells(&global.coverage_b);
+        write_row(
+            &mut out,
+            &[
+                &text_cell(&global.doc_a),
+                &text_cell(&global.doc_b),
+                &global.score.to_string(),
+                global.strategy.name(),
+                &a_sentences,
+                &a_best,
+                &a_tokens,
+                &b_sentences,
+                &b_best,
+                &b_tokens,
+            ],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Write the global scores as a symmetric document × document CSV matrix
+///
+/// The diagonal is 1; pairs without a score (no sentences left to compare)
+/// are empty cells. Rows follow the upload order, so documents with the same
+/// filename keep their own rows.
+pub fn write_similarity_matrix_csv<W: Write>(response: &SentenceAnalysisResponse, mut out: W) -> io::Result<()> {
+    let names = document_names(response);
+    let index = |name: &str, doc_index: usize| {
+        if response.documents.is_empty() {
+            names.iter().position(|n| n == name)
+        } else {
+            Some(doc_index).filter(|&i| i < names.len())
+        }
+    };
+
+    let mut scores: Vec<Vec<Option<f32>>> = vec![vec![None; names.len()]; names.len()];
+    for (i, row) in scores.iter_mut().enumerate() {
+        row[i] = Some(1.0);
+    }
+    for global in &response.global_similarity {
+        if let (Some(a), Some(b)) = (
+            index(&global.doc_a, global.doc_a_index),
+            index(&global.doc_b, global.doc_b_index),
+        ) {
+            scores[a][b] = Some(global.score);
+            scores[b][a] = Some(global.score);
+        }
+    }
+
+    write!(out, "{}", BOM)?;
+    let header: Vec<String> = std::iter::once(String::new())
+        .chain(names.iter().map(|name| text_cell(name)))
+        .collect();
+    write_row(&mut out, &header.iter().map(String::as_str).collect::<Vec<_>>())?;
+
+    for (name, row) in names.iter().zip(&scores) {
+        let cells: Vec<String> = std::iter::once(text_cell(name))
+            .chain(row.iter().map(|score| score.map(|s| s.to_string()).unwrap_or_default()))
+            .collect();
+        write_row(&mut out, &cells.iter().map(String::as_str).collect::<Vec<_>>())?;
+    }
+
+    Ok(())
+}
+
+/// Write every match as one JSON object per line
+pub fn write_matches_ndjson<W: Write>(response: &SentenceAnalysisResponse, mut out: W) -> io::Result<()> {
+    for m in &response.matches {
+        serde_json::to_writer(&mut out, m)?;
+        out.write_all(b"\n")?;
+    }
+
+    Ok(())
+}
+
+/// Document names in upload order, falling back to the order of first
+/// appearance in the global similarity list
+fn document_names(response: &SentenceAnalysisResponse) -> Vec<String> {
+    if !response.documents.is_empty() {
+        return response.documents.iter().map(|d| d.filename.clone()).collect();
+    }
+
+    let mut names: Vec<String> = Vec::new();
+    for global in &response.global_similarity {
+        for name in [&global.doc_a, &global.doc_b] {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+    }
+    names
+}
+
+fn coverage_cells(coverage: &DirectionalCoverage) -> [String; 3] {
+    [
+        coverage.sentences.to_string(),
+        coverage.best_match_mean.to_string(),
+        coverage.tokens.to_string(),
+    ]
+}
+
+/// Neutralize spreadsheet formulas in a text cell
+fn text_cell(text: &str) -> String {
+    if text.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", text)
+    } else {
+        text.to_string()
+    }
+}
+
+fn write_row<W: Write>(out: &mut W, cells: &[&str]) -> io::Result<()> {
+    let row: Vec<String> = cells.iter().map(|cell| quote(cell)).collect();
+    write!(out, "{}\r\n", row.join(","))
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or line break
+fn quote(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,208 @@
+//! Self-contained side-by-side HTML comparison report
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::core::SentenceDocument;
+use crate::models::{DirectionalCoverage, SentenceAnalysisResponse};
+
+const STYLE: &str = r#"
+body { font-family: system-ui, sans-serif; margin: 0; padding: 1.5rem; color: #1f2328; background: #f6f8fa; }
+h1 { margin-top: 0; }
+section { margin-bottom: 2rem; }
+table { border-collapse: collapse; background: #fff; width: 100%; }
+th, td { border: 1px solid #d0d7de; padding: 0.35rem 0.6rem; text-align: left; vertical-align: top; }
+th { background: #eaeef2; }
+td.num { text-align: right; font-variant-numeric: tabular-nums; }
+.documents { display: flex; gap: 1rem; overflow-x: auto; align-items: flex-start; }
+.document { flex: 1 0 24rem; background: #fff; border: 1px solid #d0d7de; padding: 0 1rem 1rem; max-height: 85vh; overflow-y: auto; }
+.document h2 { position: sticky; top: 0; background: #fff; margin: 0; padding: 0.75rem 0; font-size: 1.1rem; }
+.sentence { line-height: 1.6; }
+.grade-1 { background: #fff4c2; }
+.grade-2 { background: #ffdf8a; }
+.grade-3 { background: #ffb38a; }
+.grade-4 { background: #ff8a8a; }
+.sentence:target, tr:target { outline: 2px solid #0969da; }
+sup a { font-size: 0.7rem; margin-left: 0.1rem; color: #0969da; text-decoration: none; }
+.legend span { padding: 0 0.4rem; margin-right: 0.4rem; }
+"#;
+
+/// Render a self-contained HTML report
+///
+/// `documents` are the split documents the response was computed from, in
+/// the same order; matched sentences are highlighted in colors graded by
+/// their best similarity and link to the match table, which links back to
+/// both sentences.
+pub fn render_html_report(response: &SentenceAnalysisResponse, documents: &[SentenceDocument]) -> String {
+    // Best similarity and match numbers of every matched sentence, keyed by
+    // document index so documents with the same filename stay apart
+    let mut highlights: HashMap<(usize, usize), (f32, Vec<usize>)> = HashMap::new();
+    for (number, m) in response.matches.iter().enumerate() {
+        let sides = [
+            (m.source_doc_index, m.source_sentence_index),
+            (m.target_doc_index, m.target_sentence_index),
+        ];
+        for side in sides {
+            let entry = highlights.entry(side).or_insert((0.0, Vec::new()));
+            entry.0 = entry.0.max(m.similarity);
+            entry.1.push(number + 1);
+        }
+    }
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<title>Document similarity report</title>\n<style>");
+    html.push_str(STYLE);
+    html.push_str("</style>\n</head>\n<body>\n");
+
+    render_summary(&mut html, response);
+    render_global_similarity(&mut html, response);
+    render_matches(&mut html, response, documents);
+    render_documents(&mut html, documents, &highlights);
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn render_summary(html: &mut String, response: &SentenceAnalysisResponse) {
+    let metadata = &response.metadata;
+    let _ = write!(
+        html,
+        "<header>\n<h1>Document similarity report</h1>\n<p>{} documents &middot; {} sentences &middot; threshold {:.2} &middot; {} matches</p>\n",
+        metadata.documents_count,
+        metadata.total_sentences,
+        metadata.threshold,
+        response.matches.len()
+    );
+    for warning in &metadata.warnings {
+        let _ = writeln!(html, "<p>&#9888; {}</p>", escape(warning));
+    }
+    html.push_str("<p class=\"legend\">Similarity: <span class=\"grade-1\">&lt; 0.80</span><span class=\"grade-2\">0.80</span><span class=\"grade-3\">0.90</span><span class=\"grade-4\">0.95+</span></p>\n</header>\n");
+}
+
+fn render_global_similarity(html: &mut String, response: &SentenceAnalysisResponse) {
+    html.push_str("<section>\n<h2>Global similarity</h2>\n<table>\n");
+    html.push_str("<tr><th>Document A</th><th>Document B</th><th>Score</th><th>Strategy</th><th>A found in B</th><th>B found in A</th></tr>\n");
+    for global in &response.global_similarity {
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td class=\"num\">{:.3}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>",
+            escape(&global.doc_a),
+            escape(&global.doc_b),
+            global.score,
+            global.strategy.name(),
+            coverage(&global.coverage_a),
+            coverage(&global.coverage_b)
+        );
+    }
+    html.push_str("</table>\n</section>\n");
+}
+
+fn render_matches(html: &mut String, response: &SentenceAnalysisResponse, documents: &[SentenceDocument]) {
+    html.push_str("<section>\n<h2>Matches</h2>\n");
+    if response.matches.is_empty() {
+        html.push_str("<p>No sentence pairs reached the threshold.</p>\n</section>\n");
+        return;
+    }
+
+    html.push_str("<table>\n<tr><th>#</th><th>Similarity</th><th>Source</th><th>Target</th></tr>\n");
+    for (number, m) in response.matches.iter().enumerate() {
+        let _ = writeln!(
+            html,
+            "<tr id=\"m{}\"><td class=\"num\">{}</td><td class=\"num {}\">{:.3}</td><td>{}</td><td>{}</td></tr>",
+            number + 1,
+            number + 1,
+            grade(m.similarity),
+            m.similarity,
+            sentence_link(documents, m.source_doc_index, &m.source_doc, m.source_sentence_index, &m.source_sentence),
+            sentence_link(documents, m.target_doc_index, &m.target_doc, m.target_sentence_index, &m.target_sentence)
+        );
+    }
+    html.push_str("</table>\n</section>\n");
+}
+
+fn render_documents(
+    html: &mut String,
+    documents: &[SentenceDocument],
+    highlights: &HashMap<(usize, usize), (f32, Vec<usize>)>,
+) {
+    html.push_str("<section class=\"documents\">\n");
+    for (doc, document) in documents.iter().enumerate() {
+        let _ = writeln!(html, "<div class=\"document\">\n<h2>{}</h2>\n<p>", escape(&document.filename));
+        for (index, sentence) in document.sentences.iter().enumerate() {
+            match highlights.get(&(doc, index)) {
+                Some((similarity, numbers)) => {
+                    let _ = write!(
+                        html,
+                        "<span id=\"d{}-s{}\" class=\"sentence {}\" title=\"best similarity {:.3}\">{}</span><sup>",
+                        doc,
+                        index,
+                        grade(*similarity),
+                        similarity,
+                        escape(sentence)
+                    );
+                    for number in numbers {
+                        let _ = write!(html, "<a href=\"#m{}\">{}</a>", number, number);
+                    }
+                    html.push_str("</sup> ");
+                }
+                None => {
+                    let _ = write!(html, "<span id=\"d{}-s{}\" class=\"sentence\">{}</span> ", doc, index, escape(sentence));
+                }
+            }
+        }
+        html.push_str("</p>\n</div>\n");
+    }
+    html.push_str("</section>\n");
+}
+
+/// Link to a sentence in the side-by-side view, or plain text when the document is unknown
+fn sentence_link(documents: &[SentenceDocument], doc: usize, filename: &str, sentence: usize, text: &str) -> String {
+    let known = documents
+        .get(doc)
+        .is_some_and(|document| document.filename == filename && sentence < document.sentences.len());
+    if known {
+        format!(
+            "<a href=\"#d{}-s{}\">{} [{}]</a><br>{}",
+            doc,
+            sentence,
+            escape(filename),
+            sentence,
+            escape(text)
+        )
+    } else {
+        format!("{} [{}]<br>{}", escape(filename), sentence, escape(text))
+    }
+}
+
+fn coverage(coverage: &DirectionalCoverage) -> String {
+    format!("{:.0}%", coverage.sentences * 100.0)
+}
+
+/// CSS class for a similarity score
+fn grade(similarity: f32) -> &'static str {
+    if similarity >= 0.95 {
+        "grade-4"
+    } else if similarity >= 0.90 {
+        "grade-3"
+    } else if similarity >= 0.80 {
+        "grade-2"
+    } else {
+        "grade-1"
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,115 @@
+//! Human-readable reports and exports of analysis results
+
+mod export;
+mod html;
+
+use std::io;
+
+use crate::core::SentenceDocument;
+use crate::models::SentenceAnalysisResponse;
+
+pub use export::{
+    write_global_similarity_csv, write_matches_csv, write_matches_ndjson, write_similarity_matrix_csv,
+};
+pub use html::render_html_report;
+
+/// Output format of an analysis result
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReportFormat {
+    /// The `SentenceAnalysisResponse` as JSON
+    #[default]
+    Json,
+    /// Self-contained side-by-side HTML report
+    Html,
+    /// Matches as CSV
+    Csv,
+    /// Global similarity of every document pair as CSV
+    GlobalCsv,
+    /// Document × document global score matrix as CSV
+    MatrixCsv,
+    /// Matches as JSON Lines
+    Ndjson,
+}
+
+impl ReportFormat {
+    /// Parse a format name such as `json`, `html`, `csv`, `global_csv`, `matrix_csv` or `ndjson`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().replace('-', "_").as_str() {
+            "json" => Some(ReportFormat::Json),
+            "html" => Some(ReportFormat::Html),
+            "csv" | "matches_csv" => Some(ReportFormat::Csv),
+            "global_csv" => Some(ReportFormat::GlobalCsv),
+            "matrix_csv" | "matrix" => Some(ReportFormat::MatrixCsv),
+            "ndjson" | "jsonl" => Some(ReportFormat::Ndjson),
+            _ => None,
+        }
+    }
+
+    /// Pick a format from an `Accept` header
+    ///
+    /// The supported media type with the highest `q` weight wins, the first
+    /// listed on a tie; `q=0` marks a type as not acceptable.
+    pub fn from_accept(accept: &str) -> Option<Self> {
+        let mut best: Option<(Self, f32)> = None;
+        for range in accept.split(',') {
+            let mut parts = range.split(';');
+            let media = parts.next().unwrap_or("").trim().to_lowercase();
+            let format = match media.as_str() {
+                "application/json" => ReportFormat::Json,
+                "text/html" => ReportFormat::Html,
+                "text/csv" => ReportFormat::Csv,
+                "application/x-ndjson" | "application/jsonl" | "application/jsonlines" => ReportFormat::Ndjson,
+                _ => continue,
+            };
+            let quality = parts
+                .filter_map(|param| param.split_once('='))
+                .find(|(name, _)| name.trim().eq_ignore_ascii_case("q"))
+                .map_or(1.0, |(_, value)| value.trim().parse::<f32>().unwrap_or(0.0));
+            if quality > 0.0 && best.is_none_or(|(_, best_quality)| quality > best_quality) {
+                best = Some((format, quality));
+            }
+        }
+        best.map(|(format, _)| format)
+    }
+
+    /// Media type of the rendered output
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ReportFormat::Json => "application/json",
+            ReportFormat::Html => "text/html; charset=utf-8",
+            ReportFormat::Csv | ReportFormat::GlobalCsv | ReportFormat::MatrixCsv => "text/csv; charset=utf-8",
+            ReportFormat::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    /// Default file name of the rendered output
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            ReportFormat::Json => "report.json",
+            ReportFormat::Html => "report.html",
+            ReportFormat::Csv => "matches.csv",
+            ReportFormat::GlobalCsv => "global_similarity.csv",
+            ReportFormat::MatrixCsv => "similarity_matrix.csv",
+            ReportFormat::Ndjson => "matches.ndjson",
+        }
+    }
+}
+
+/// Render an analysis result in the given format
+///
+/// `documents` are only used by the HTML report.
+pub fn write_report<W: io::Write>(
+    format: ReportFormat,
+    response: &SentenceAnalysisResponse,
+    documents: &[SentenceDocument],
+    mut out: W,
+) -> io::Result<()> {
+    match format {
+        ReportFormat::Json => serde_json::to_writer_pretty(&mut out, response).map_err(io::Error::from),
+        ReportFormat::Html => out.write_all(render_html_report(response, documents).as_bytes()),
+        ReportFormat::Csv => write_matches_csv(response, out),
+        ReportFormat::GlobalCsv => write_global_similarity_csv(response, out),
+        ReportFormat::MatrixCsv => write_similarity_matrix_csv(response, out),
+        ReportFormat::Ndjson => write_matches_ndjson(response, out),
+    }
+}
@@ -0,0 +1,91 @@
+//! Per-language abbreviation lists used by the sentence splitter
+//!
+//! Entries are lowercase and without the trailing period. Abbreviations with
+//! internal periods (`e.g`, `s.d`) are listed as written.
+
+use super::Language;
+
+/// English titles that precede a name, so a capitalized next word does not start a sentence
+const ENGLISH_TITLES: &[&str] = &[
+    "mr", "mrs", "ms", "dr", "prof", "sr", "jr", "st", "rev", "hon", "gen", "col", "capt",
+    "lt", "sgt", "gov", "sen", "rep", "pres", "mt", "messrs", "mme", "mlle",
+];
+
+/// Other English abbreviations
+const ENGLISH: &[&str] = &[
+    "e.g", "i.e", "etc", "vs", "al", "cf", "viz", "ca", "approx", "dept", "est", "inc", "ltd",
+    "co", "corp", "bros", "univ", "assn", "jan", "feb", "apr", "jun", "jul", "aug", "sep",
+    "sept", "oct", "nov", "dec", "no", "nos", "vol", "vols", "ed", "eds", "fig", "figs", "eq",
+    "eqs", "ch", "sec", "p", "pp", "op", "cit", "ibid", "resp", "min", "max", "a.m", "p.m",
+];
+
+/// Indonesian titles that precede a name
+const INDONESIAN_TITLES: &[&str] = &[
+    "bpk", "sdr", "sdri", "yth", "prof", "dr", "ir", "drs", "dra", "hj", "kh", "jl", "tn", "ny",
+    "nn", "kec", "kab", "kel", "prov",
+];
+
+/// Other Indonesian abbreviations
+const INDONESIAN: &[&str] = &[
+    "dll", "dsb", "dst", "dkk", "tsb", "yg", "dg", "dgn", "hlm", "no", "tgl", "thn", "sbb",
+    "spt", "kpd", "utk", "krn", "bln", "a.n", "u.p", "s.d", "d.a", "vol",
+];
+
+/// English words that often start a sentence, so after a single capital
+/// letter ("Plan B. Then") they start a new sentence rather than a name
+const ENGLISH_STARTERS: &[&str] = &[
+    "a", "an", "the", "this", "that", "these", "those", "it", "its", "i", "he", "she", "we",
+    "they", "you", "my", "our", "his", "her", "their", "there", "then", "but", "and", "or", "so",
+    "yet", "if", "when", "while", "after", "before", "however", "also", "in", "on", "at", "for",
+    "from", "with", "by", "as", "to", "of", "all", "some", "no", "not", "each", "every", "what",
+    "which", "who", "why", "how", "where", "here", "now", "finally", "first", "next", "thus",
+];
+
+/// Indonesian words that often start a sentence
+const INDONESIAN_STARTERS: &[&str] = &[
+    "ini", "itu", "dia", "ia", "saya", "aku", "kami", "kita", "mereka", "anda", "namun", "tetapi",
+    "dan", "atau", "lalu", "kemudian", "setelah", "sebelum", "jika", "ketika", "saat", "di", "ke",
+    "dari", "pada", "dengan", "untuk", "dalam", "yang", "hal", "oleh", "karena", "sehingga",
+    "selain", "akhirnya", "pertama", "selanjutnya", "juga", "tidak", "semua", "setiap",
+];
+
+/// Whether `word` (without its trailing period) is a title that precedes a name
+pub(crate) fn is_title(word: &str, language: Language) -> bool {
+    let word = word.to_lowercase();
+    lists(language, ENGLISH_TITLES, INDONESIAN_TITLES).any(|list| list.contains(&word.as_str()))
+}
+
+/// Whether `word` (without its trailing period) is a known abbreviation
+pub(crate) fn is_abbreviation(word: &str, language: Language) -> bool {
+    let word = word.to_lowercase();
+    lists(language, ENGLISH, INDONESIAN).any(|list| list.contains(&word.as_str())) || is_title(&word, language)
+}
+
+/// Whether `word` is a common sentence-starting word rather than a name
+pub(crate) fn is_sentence_starter(word: &str, language: Language) -> bool {
+    let word = word.to_lowercase();
+    lists(language, ENGLISH_STARTERS, INDONESIAN_STARTERS).any(|list| list.contains(&word.as_str()))
+}
+
+/// Word lists that apply to a language
+fn lists(
+    language: Language,
+    english_list: &'static [&'static str],
+    indonesian_list: &'static [&'static str],
+) -> impl Iterator<Item = &'static [&'static str]> {
+    let (english, indonesian) = match language {
+        Language::English => (true, false),
+        Language::Indonesian => (false, true),
+        Language::Auto => (true, true),
+        Language::Chinese
+        | Language::Japanese
+        | Language::Korean
+        | Language::Arabic
+        | Language::Hindi => (false, false),
+    };
+
+    english
+        .then_some(english_list)
+        .into_iter()
+        .chain(indonesian.then_some(indonesian_list))
+}
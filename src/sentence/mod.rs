@@ -1,57 +1,69 @@
 //! Sentence splitting module
 
-use lazy_static::lazy_static;
-use regex::Regex;
+mod abbreviations;
+mod quotes;
+mod references;
+mod splitter;
 
-lazy_static! {
-    /// Regex pattern for splitting sentences
-    /// Matches punctuation (. ! ?) followed by whitespace (including newlines)
-    /// or end of string
-    static ref SENTENCE_SPLITTER: Regex = Regex::new(r"[.!?](?:\s+|$)").unwrap();
+pub use quotes::{has_citation, quoted_sentences};
+pub use references::find_reference_section;
+
+/// Language hint for sentence splitting
+///
+/// Selects the abbreviation lists used to tell abbreviation periods
+/// ("Dr.", "dkk.") from sentence-ending periods. `Auto` uses all lists.
+/// Languages without abbreviation lists rely on the Unicode sentence
+/// boundary rules alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    #[default]
+    Auto,
+    English,
+    Indonesian,
+    Chinese,
+    Japanese,
+    Korean,
+    Arabic,
+    Hindi,
+}
+
+impl Language {
+    /// Parse a language hint such as `en` or `indonesian`
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code.trim().to_lowercase().as_str() {
+            "auto" => Some(Language::Auto),
+            "en" | "eng" | "english" => Some(Language::English),
+            "id" | "ind" | "indonesian" => Some(Language::Indonesian),
+            "zh" | "zho" | "chi" | "chinese" => Some(Language::Chinese),
+            "ja" | "jpn" | "japanese" => Some(Language::Japanese),
+            "ko" | "kor" | "korean" => Some(Language::Korean),
+            "ar" | "ara" | "arabic" => Some(Language::Arabic),
+            "hi" | "hin" | "hindi" => Some(Language::Hindi),
+            _ => None,
+        }
+    }
 }
 
-/// Split text into sentences using regex
+/// Split text into sentences using all known abbreviation lists
 pub fn split_sentences(text: &str) -> Vec<String> {
+    split_sentences_with_language(text, Language::Auto)
+}
+
+/// Split text into sentences
+///
+/// Boundaries follow the Unicode sentence rules (UAX #29): sentences end at
+/// `.`, `!`, `?`, an ellipsis, and the terminators of other scripts such as
+/// `。！？` (Chinese, Japanese), `؟` (Arabic) and `।` (Hindi), with closing
+/// quotes and brackets kept in the sentence. Periods of
+/// abbreviations ("e.g.", "et al."), initials ("J. Smith"), titles
+/// ("Dr. Smith") and numbers are not boundaries, and neither is any
+/// terminator followed by a lowercase word. Paragraph breaks (blank lines)
+/// and bullet or numbered list items always start a new sentence; list
+/// markers are removed.
+pub fn split_sentences_with_language(text: &str, language: Language) -> Vec<String> {
     if text.trim().is_empty() {
         return vec![];
     }
 
-    // Collect all matches with their positions
-    let matches: Vec<_> = SENTENCE_SPLITTER.find_iter(text).collect();
-    
-    if matches.is_empty() {
-        // No sentence delimiters found, return entire text as one sentence
-        return vec![text.trim().to_string()].into_iter()
-            .filter(|s| !s.is_empty())
-            .collect();
-    }
-
-    // Build sentences from matches using functional approach
-    let sentence_ranges = matches.iter().enumerate().map(|(idx, mat)| {
-        let start = if idx == 0 { 0 } else { matches[idx - 1].end() };
-        let end = mat.start() + 1; // +1 to include punctuation
-        (start, end)
-    });
-
-    // Collect sentences from ranges
-    let sentences_from_ranges: Vec<String> = sentence_ranges
-        .map(|(start, end)| text[start..end].trim().to_string())
-        .filter(|s| !s.is_empty())
-        .collect();
-
-    // Add remaining text after last match if exists
-    let remaining_sentence = matches.last()
-        .and_then(|last_match| {
-            let remaining = text[last_match.end()..].trim();
-            if remaining.is_empty() {
-                None
-            } else {
-                Some(remaining.to_string())
-            }
-        });
-
-    // Combine sentences with optional remaining
-    sentences_from_ranges.into_iter()
-        .chain(remaining_sentence)
-        .collect()
+    splitter::split(text, language)
 }
@@ -0,0 +1,100 @@
+//! Quotation and in-text citation detection
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+/// Share of a sentence's characters that must lie inside quotes for the
+/// sentence to count as quoted
+const QUOTED_SHARE: f32 = 0.5;
+
+/// Number of sentences after which an unclosed quote is treated as a stray mark
+const MAX_QUOTE_SENTENCES: usize = 5;
+
+lazy_static! {
+    /// Parenthetical APA/Harvard citation: "(Smith, 2020)", "(Smith & Lee, 2019a, p. 4)"
+    static ref PARENTHETICAL: Regex =
+        Regex::new(r"\([^()\d]*\p{Lu}[^()\d]*,?\s+(?:1[5-9]|20)\d{2}[a-z]?\b[^()]*\)").unwrap();
+
+    /// Narrative citation: "Smith (2020)", "Smith et al. (2019)"
+    static ref NARRATIVE: Regex =
+        Regex::new(r"\p{Lu}[\p{L}'’-]+(?:\s+(?:et al\.|dkk\.))?\s+\((?:1[5-9]|20)\d{2}[a-z]?\)").unwrap();
+
+    /// Numeric IEEE citation: "[12]", "[3, 5]", "[4–6]"
+    static ref NUMERIC: Regex = Regex::new(r"\[\d{1,3}(?:\s*[,–-]\s*\d{1,3})*\]").unwrap();
+}
+
+/// Whether a sentence contains an in-text citation (APA, Harvard or IEEE style)
+pub fn has_citation(sentence: &str) -> bool {
+    PARENTHETICAL.is_match(sentence) || NARRATIVE.is_match(sentence) || NUMERIC.is_match(sentence)
+}
+
+/// For each sentence of a document, whether it lies inside a quotation
+///
+/// Quotes are tracked across sentence boundaries, so every sentence of a
+/// multi-sentence quotation is detected. Curly (`“ ”`), straight (`"`),
+/// guillemet (`« »`), low-9 (`„`) and CJK corner (`「 」`, `『 』`) quotes are
+/// recognized; single quotes are ignored since they double as apostrophes.
+/// Each opening mark expects its own closing mark, so German `„Zitat“` closes
+/// on `“` even though `“` opens an English quote.
+/// A sentence is quoted when at least half of its characters are inside quotes.
+pub fn quoted_sentences(sentences: &[String]) -> Vec<bool> {
+    // Closing marks expected by the quotes currently open, innermost last
+    let mut open: Vec<char> = Vec::new();
+    let mut straight_open = false;
+    let mut open_for = 0usize;
+
+    sentences
+        .iter()
+        .map(|sentence| {
+            let mut inside = 0usize;
+            let mut total = 0usize;
+
+            for c in sentence.chars() {
+                match c {
+                    c if open.last() == Some(&c) => {
+                        open.pop();
+                    }
+                    c if closing_mark(c).is_some() => open.extend(closing_mark(c)),
+                    // A mismatched closing mark ("„…”") still closes the innermost quote
+                    '”' | '»' | '」' | '』' => {
+                        open.pop();
+                    }
+                    '"' => straight_open = !straight_open,
+                    c if c.is_whitespace() => {}
+                    _ => {
+                        total += 1;
+                        if !open.is_empty() || straight_open {
+                            inside += 1;
+                        }
+                    }
+                }
+            }
+
+            // Forget quotes left open for too long: most likely an unbalanced mark
+            if !open.is_empty() || straight_open {
+                open_for += 1;
+                if open_for >= MAX_QUOTE_SENTENCES {
+                    open.clear();
+                    straight_open = false;
+                    open_for = 0;
+                }
+            } else {
+                open_for = 0;
+            }
+
+            total > 0 && inside as f32 / total as f32 >= QUOTED_SHARE
+        })
+        .collect()
+}
+
+/// Closing mark of an opening quotation mark
+fn closing_mark(open: char) -> Option<char> {
+    match open {
+        '“' => Some('”'),
+        '«' => Some('»'),
+        '„' => Some('“'),
+        '「' => Some('」'),
+        '『' => Some('』'),
+        _ => None,
+    }
+}
@@ -0,0 +1,49 @@
+//! Bibliography / reference section detection
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    /// Reference section heading, optionally numbered ("7.", "VII."), at the start of a sentence
+    static ref HEADING: Regex = Regex::new(
+        r"(?i)^(?:(?:\d{1,2}|[ivxlc]{1,6})[.)]?\s+)?(?:references?|bibliography|bibliografi|works cited|literature cited|sources|daftar pustaka|daftar referensi|referensi|kepustakaan|pustaka acuan)(?:\s*[:.])?"
+    )
+    .unwrap();
+}
+
+/// Index of the first sentence of a trailing reference section
+///
+/// Looks for a heading such as "References", "Bibliography" or "Daftar
+/// Pustaka", either alone or followed directly by the first entry when the
+/// extractor joined the lines ("References [1] A. Smith, ..."). Only
+/// headings in the second half of the document count, and the last one wins,
+/// so a mention in the introduction or table of contents is ignored.
+pub fn find_reference_section(sentences: &[String]) -> Option<usize> {
+    let first_candidate = sentences.len() / 2;
+
+    sentences
+        .iter()
+        .enumerate()
+        .skip(first_candidate)
+        .rev()
+        .find(|(_, sentence)| is_heading(sentence))
+        .map(|(index, _)| index)
+}
+
+fn is_heading(sentence: &str) -> bool {
+    let sentence = sentence.trim();
+    let Some(heading) = HEADING.find(sentence) else {
+        return false;
+    };
+
+    // Anything after the heading must look like the start of an entry,
+    // not a sentence that merely begins with the word ("References to ...")
+    let rest = &sentence[heading.end()..];
+    match rest.trim_start().chars().next() {
+        None => true,
+        Some(c) => {
+            rest.starts_with(char::is_whitespace)
+                && (c.is_uppercase() || c.is_ascii_digit() || c == '[')
+        }
+    }
+}
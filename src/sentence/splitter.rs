@@ -0,0 +1,210 @@
+//! Rule-based sentence boundary detection
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::abbreviations::{is_abbreviation, is_sentence_starter, is_title};
+use super::Language;
+
+lazy_static! {
+    /// Bullet or numbered list marker at the start of a line
+    static ref LIST_MARKER: Regex =
+        Regex::new(r"^(?:[-*•‣◦▪–]|\(?\d{1,3}[.)]|\(?[a-zA-Z]\))\s+").unwrap();
+
+    /// Ellipsis character with optional closing punctuation and trailing whitespace
+    static ref ELLIPSIS: Regex = Regex::new(r#"…["'”’»)\]}]*\s+"#).unwrap();
+}
+
+/// Split text into sentences
+///
+/// Text is first cut into blocks at paragraph breaks (blank lines) and list
+/// items, then every block is split at sentence boundaries.
+pub(crate) fn split(text: &str, language: Language) -> Vec<String> {
+    blocks(text)
+        .iter()
+        .flat_map(|block| split_block(block, language))
+        .collect()
+}
+
+/// Group lines into blocks: a blank line ends a block and every list item
+/// starts a new one. Wrapped lines are joined with a single space.
+fn blocks(text: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut current = String::new();
+
+    for line in text.lines() {
+        let line = line.trim();
+
+        if line.is_empty() {
+            flush(&mut current, &mut blocks);
+            continue;
+        }
+
+        let line = match LIST_MARKER.find(line) {
+            Some(marker) => {
+                flush(&mut current, &mut blocks);
+                &line[marker.end()..]
+            }
+            None => line,
+        };
+
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(line);
+    }
+    flush(&mut current, &mut blocks);
+
+    blocks
+}
+
+fn flush(current: &mut String, blocks: &mut Vec<String>) {
+    if !current.is_empty() {
+        blocks.push(std::mem::take(current));
+    }
+}
+
+/// Split a single block into sentences
+///
+/// Uses the Unicode sentence boundaries of UAX #29, which handle `.`, `!`,
+/// `?` and the terminators of other scripts (`。`, `？`, `؟`, `।`, ...),
+/// closing punctuation, decimals and periods followed by a lowercase word.
+/// Breaks after initials, titles and abbreviations are then undone, and
+/// breaks after an ellipsis before a capitalized word are added.
+fn split_block(block: &str, language: Language) -> Vec<String> {
+    let segments: Vec<&str> = block
+        .split_sentence_bounds()
+        .flat_map(split_after_ellipsis)
+        .collect();
+
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+
+    for (index, segment) in segments.iter().enumerate() {
+        current.push_str(segment);
+
+        let ends_sentence = segments
+            .get(index + 1)
+            .is_none_or(|next| !continues_sentence(&current, next, language));
+
+        if ends_sentence {
+            push_sentence(&current, &mut sentences);
+            current.clear();
+        }
+    }
+
+    sentences
+}
+
+fn push_sentence(sentence: &str, sentences: &mut Vec<String>) {
+    let sentence = sentence.trim();
+    if !sentence.is_empty() {
+        sentences.push(sentence.to_string());
+    }
+}
+
+/// Whether the sentence continues after a UAX #29 break between `segment` and `next`
+///
+/// UAX #29 breaks after every period followed by a capitalized word, which
+/// is wrong after initials ("J. Smith"), titles ("Dr. Smith") and most
+/// abbreviations.
+fn continues_sentence(segment: &str, next: &str, language: Language) -> bool {
+    let trimmed = segment.trim_end().trim_end_matches(is_closing);
+    let Some(body) = trimmed.strip_suffix('.') else {
+        return false;
+    };
+
+    // Ellipsis: keep the UAX #29 decision
+    if body.ends_with('.') {
+        return false;
+    }
+
+    let word = last_word(body);
+    if is_title(&word, language) {
+        return true;
+    }
+    if is_initial(&word) {
+        return follows_initial(body) || starts_with_name(next, language);
+    }
+
+    // Abbreviations end a sentence only before a capitalized word ("etc. The")
+    if is_abbreviation(&word, language) || is_dotted(&word) {
+        let next_letter = next.chars().find(|c| !c.is_whitespace() && !is_opening(*c));
+        return !next_letter.is_some_and(char::is_uppercase);
+    }
+
+    false
+}
+
+/// Split a segment after a `…` followed by whitespace and a capitalized word
+///
+/// UAX #29 does not treat the ellipsis character as a terminator.
+fn split_after_ellipsis(segment: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0usize;
+
+    for mat in ELLIPSIS.find_iter(segment) {
+        let next_letter = segment[mat.end()..]
+            .chars()
+            .find(|c| !is_opening(*c));
+        if next_letter.is_some_and(char::is_uppercase) {
+            parts.push(&segment[start..mat.end()]);
+            start = mat.end();
+        }
+    }
+    parts.push(&segment[start..]);
+
+    parts
+}
+
+/// The last whitespace-separated word of `text`, without leading opening punctuation
+fn last_word(text: &str) -> String {
+    text.rsplit(char::is_whitespace)
+        .next()
+        .unwrap_or("")
+        .chars()
+        .skip_while(|c| is_opening(*c))
+        .collect()
+}
+
+/// A single capital letter such as the "J" in "J. Smith"
+fn is_initial(word: &str) -> bool {
+    let mut letters = word.chars();
+    matches!((letters.next(), letters.next()), (Some(c), None) if c.is_uppercase())
+}
+
+/// Whether the word before the last one of `body` is an initial too ("J. R.")
+fn follows_initial(body: &str) -> bool {
+    body.rsplit(char::is_whitespace)
+        .nth(1)
+        .and_then(|word| word.strip_suffix('.'))
+        .is_some_and(|word| is_initial(&last_word(word)))
+}
+
+/// Whether `next` starts with a capitalized word that is not a common
+/// sentence starter, i.e. probably the name after an initial ("F. Kennedy")
+fn starts_with_name(next: &str, language: Language) -> bool {
+    let word: String = next
+        .split_whitespace()
+        .next()
+        .unwrap_or("")
+        .chars()
+        .skip_while(|c| is_opening(*c))
+        .take_while(|c| c.is_alphabetic())
+        .collect();
+    word.chars().next().is_some_and(char::is_uppercase) && !is_sentence_starter(&word, language)
+}
+
+/// Dotted abbreviations and initialisms ("U.S", "S.Kom", "Ph.D")
+fn is_dotted(word: &str) -> bool {
+    word.contains('.') && word.chars().all(|c| c.is_alphabetic() || c == '.')
+}
+
+fn is_closing(c: char) -> bool {
+    matches!(c, '"' | '\'' | '”' | '’' | '»' | ')' | ']' | '}')
+}
+
+fn is_opening(c: char) -> bool {
+    matches!(c, '"' | '\'' | '“' | '‘' | '«' | '(' | '[' | '{')
+}
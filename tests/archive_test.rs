@@ -0,0 +1,149 @@
+//! Tests for ZIP archive expansion
+
+use std::io::{Cursor, Write};
+
+use document_similarity_analyzer::extraction::{
+    detect_file_type, expand_archive, ArchiveError, ArchiveLimits, FileType,
+};
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+/// Build an in-memory ZIP archive from `(path, content)` pairs
+fn build_zip(entries: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    for (path, content) in entries {
+        if path.ends_with('/') {
+            writer.add_directory(*path, FileOptions::default()).unwrap();
+        } else {
+            writer.start_file(*path, FileOptions::default()).unwrap();
+            writer.write_all(content).unwrap();
+        }
+    }
+    writer.finish().unwrap().into_inner()
+}
+
+#[test]
+fn test_plain_zip_is_detected_as_archive() {
+    let archive = build_zip(&[("essay.txt", b"Some text.")]);
+
+    let detection = detect_file_type(&archive, "submissions", None).unwrap();
+
+    assert_eq!(detection.file_type, FileType::Zip);
+}
+
+#[test]
+fn test_unsupported_office_packages_are_not_archives() {
+    let xlsx = build_zip(&[
+        ("[Content_Types].xml", b"<Types/>"),
+        ("xl/workbook.xml", b"<workbook/>"),
+    ]);
+    let ods = build_zip(&[
+        ("mimetype", b"application/vnd.oasis.opendocument.spreadsheet"),
+        ("content.xml", b"<office:document-content/>"),
+    ]);
+
+    assert_eq!(detect_file_type(&xlsx, "grades.xlsx", None), None);
+    assert_eq!(detect_file_type(&ods, "grades.ods", None), None);
+    assert_eq!(detect_file_type(&ods, "upload", None), None);
+
+    // The `.zip` extension asks for expansion regardless of the content
+    let named_zip = detect_file_type(&ods, "grades.zip", None).unwrap();
+    assert_eq!(named_zip.file_type, FileType::Zip);
+}
+
+#[test]
+fn test_expands_supported_entries_labeled_by_path() {
+    let archive = build_zip(&[
+        ("submissions/", b""),
+        ("submissions/alice/essay.txt", b"Alice wrote this."),
+        ("submissions/bob/essay.md", b"# Bob\n\nBob wrote this."),
+        ("submissions/bob/photo.jpg", b"\xFF\xD8\xFF"),
+        ("__MACOSX/submissions/._essay.txt", b"resource fork"),
+        ("submissions/.DS_Store", b"finder"),
+    ]);
+
+    let expanded = expand_archive(&archive, &ArchiveLimits::default()).unwrap();
+    let paths: Vec<&str> = expanded.entries.iter().map(|e| e.path.as_str()).collect();
+
+    assert_eq!(paths, vec!["submissions/alice/essay.txt", "submissions/bob/essay.md"]);
+    assert_eq!(expanded.entries[0].data, b"Alice wrote this.");
+    assert_eq!(expanded.skipped.len(), 3);
+}
+
+#[test]
+fn test_expands_nested_archives_up_to_max_depth() {
+    let inner = build_zip(&[("inner.txt", b"Inner document.")]);
+    let outer = build_zip(&[("outer.txt", b"Outer document."), ("nested.zip", &inner)]);
+
+    let expanded = expand_archive(&outer, &ArchiveLimits::default()).unwrap();
+    let paths: Vec<&str> = expanded.entries.iter().map(|e| e.path.as_str()).collect();
+    assert_eq!(paths, vec!["outer.txt", "nested.zip/inner.txt"]);
+
+    let flat_only = ArchiveLimits { max_depth: 1, ..ArchiveLimits::default() };
+    assert!(matches!(
+        expand_archive(&outer, &flat_only),
+        Err(ArchiveError::TooDeep(1))
+    ));
+}
+
+#[test]
+fn test_expands_nested_archive_without_zip_extension() {
+    let inner = build_zip(&[("inner.txt", b"Inner document.")]);
+    let outer = build_zip(&[("outer.txt", b"Outer document."), ("bundle", &inner)]);
+
+    let expanded = expand_archive(&outer, &ArchiveLimits::default()).unwrap();
+    let paths: Vec<&str> = expanded.entries.iter().map(|e| e.path.as_str()).collect();
+    assert_eq!(paths, vec!["outer.txt", "bundle/inner.txt"]);
+
+    // Content-detected archives count against the same depth limit
+    let flat_only = ArchiveLimits { max_depth: 1, ..ArchiveLimits::default() };
+    assert!(matches!(
+        expand_archive(&outer, &flat_only),
+        Err(ArchiveError::TooDeep(1))
+    ));
+}
+
+#[test]
+fn test_skips_entries_without_extension() {
+    let archive = build_zip(&[("essay.txt", b"Essay."), ("mimetype", b"application/zip")]);
+
+    let expanded = expand_archive(&archive, &ArchiveLimits::default()).unwrap();
+
+    let paths: Vec<&str> = expanded.entries.iter().map(|e| e.path.as_str()).collect();
+    assert_eq!(paths, vec!["essay.txt"]);
+    assert_eq!(expanded.skipped, vec!["mimetype"]);
+}
+
+#[test]
+fn test_enforces_entry_count_limit() {
+    let archive = build_zip(&[("a.txt", b"A."), ("b.txt", b"B."), ("c.txt", b"C.")]);
+    let limits = ArchiveLimits { max_entries: 2, ..ArchiveLimits::default() };
+
+    assert!(matches!(
+        expand_archive(&archive, &limits),
+        Err(ArchiveError::TooManyEntries(2))
+    ));
+}
+
+#[test]
+fn test_enforces_uncompressed_size_limit() {
+    // Highly compressible content: small archive, large expansion
+    let bomb = vec![b'a'; 64 * 1024];
+    let archive = build_zip(&[("bomb.txt", &bomb)]);
+    assert!(archive.len() < bomb.len() / 10);
+
+    let limits = ArchiveLimits { max_total_size: 16 * 1024, ..ArchiveLimits::default() };
+
+    assert!(matches!(
+        expand_archive(&archive, &limits),
+        Err(ArchiveError::TooLarge(_))
+    ));
+}
+
+#[test]
+fn test_rejects_invalid_archive() {
+    assert!(matches!(
+        expand_archive(b"PK\x03\x04 truncated", &ArchiveLimits::default()),
+        Err(ArchiveError::Invalid(_))
+    ));
+}
@@ -0,0 +1,134 @@
+//! Tests for document clustering
+
+use document_similarity_analyzer::core::{
+    agglomerative_clustering, analyze_sentence_similarity_with_options, cluster_documents,
+    connected_components, cut_dendrogram, SentenceAnalysisOptions, SentenceDocument,
+};
+use document_similarity_analyzer::models::{ClusterMethod, SimilarityMatrix};
+
+/// Two tight pairs {0, 1} and {2, 3}, loosely connected through 1 and 2
+fn matrix() -> Vec<Vec<f32>> {
+    vec![
+        vec![1.0, 0.9, 0.1, 0.2],
+        vec![0.9, 1.0, 0.4, 0.1],
+        vec![0.1, 0.4, 1.0, 0.8],
+        vec![0.2, 0.1, 0.8, 1.0],
+    ]
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < 1e-6,
+        "expected {expected}, got {actual}"
+    );
+}
+
+#[test]
+fn test_agglomerative_merge_order_and_heights() {
+    for method in [
+        ClusterMethod::Single,
+        ClusterMethod::Average,
+        ClusterMethod::Complete,
+    ] {
+        let merges = agglomerative_clustering(&matrix(), method);
+
+        assert_eq!(merges.len(), 3);
+        assert_eq!((merges[0].left, merges[0].right, merges[0].size), (0, 1, 2));
+        assert_close(merges[0].height, 0.1);
+        assert_eq!((merges[1].left, merges[1].right, merges[1].size), (2, 3, 2));
+        assert_close(merges[1].similarity, 0.8);
+        // The last merge joins the two new clusters (nodes 4 and 5)
+        assert_eq!((merges[2].left, merges[2].right, merges[2].size), (4, 5, 4));
+        assert!(merges.windows(2).all(|w| w[0].height <= w[1].height));
+    }
+}
+
+#[test]
+fn test_linkage_heights_differ_by_method() {
+    let top = |method| agglomerative_clustering(&matrix(), method)[2].similarity;
+
+    assert_close(top(ClusterMethod::Single), 0.4);
+    assert_close(top(ClusterMethod::Average), (0.1 + 0.2 + 0.4 + 0.1) / 4.0);
+    assert_close(top(ClusterMethod::Complete), 0.1);
+}
+
+#[test]
+fn test_cut_dendrogram_matches_connected_components() {
+    let merges = agglomerative_clustering(&matrix(), ClusterMethod::Single);
+
+    assert_eq!(cut_dendrogram(4, &merges, 0.5), vec![0, 0, 1, 1]);
+    assert_eq!(cut_dendrogram(4, &merges, 0.3), vec![0, 0, 0, 0]);
+    assert_eq!(cut_dendrogram(4, &merges, 0.95), vec![0, 1, 2, 3]);
+    assert_eq!(connected_components(&matrix(), 0.5), vec![0, 0, 1, 1]);
+    assert_eq!(connected_components(&matrix(), 0.3), vec![0, 0, 0, 0]);
+}
+
+#[test]
+fn test_cluster_documents_reports_groups() {
+    let names: Vec<String> = ["a", "b", "c", "d", "e"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let mut similarity = vec![vec![0.0; 5]; 5];
+    for (i, row) in similarity.iter_mut().enumerate() {
+        row[i] = 1.0;
+    }
+    for (i, j) in [(0, 3), (1, 4)] {
+        similarity[i][j] = 0.9;
+        similarity[j][i] = 0.9;
+    }
+    let matrix = SimilarityMatrix::new(similarity, names);
+
+    let clustering = cluster_documents(&matrix, ClusterMethod::Average, 0.5);
+
+    assert_eq!(clustering.assignments, vec![0, 1, 2, 0, 1]);
+    assert_eq!(clustering.clusters.len(), 2);
+    assert_eq!(clustering.clusters[0].documents, vec!["a", "d"]);
+    assert_eq!(clustering.clusters[1].id, 1);
+    assert_eq!(clustering.clusters[1].documents, vec!["b", "e"]);
+    assert_eq!(clustering.dendrogram.len(), 4);
+
+    let components = cluster_documents(&matrix, ClusterMethod::Components, 0.5);
+    assert_eq!(components.assignments, clustering.assignments);
+    assert!(components.dendrogram.is_empty());
+}
+
+#[test]
+fn test_pipeline_clusters_copied_documents() {
+    let copied = "Glaciers carve deep valleys over thousands of years.";
+    let documents = vec![
+        SentenceDocument::new("a.txt".to_string(), vec![copied.to_string()]),
+        SentenceDocument::new(
+            "b.txt".to_string(),
+            vec!["Rivers meander slowly across wide floodplains.".to_string()],
+        ),
+        SentenceDocument::new("c.txt".to_string(), vec![copied.to_string()]),
+    ];
+    let options = SentenceAnalysisOptions {
+        clustering: Some(ClusterMethod::Complete),
+        ..SentenceAnalysisOptions::default()
+    };
+
+    let analysis = analyze_sentence_similarity_with_options(&documents, &options);
+    let clustering = analysis.clustering.unwrap();
+
+    assert_eq!(clustering.assignments, vec![0, 1, 0]);
+    assert_eq!(clustering.clusters.len(), 1);
+    assert_eq!(clustering.clusters[0].documents, vec!["a.txt", "c.txt"]);
+    // Matrices are only returned when requested
+    assert!(analysis.sentence_matrix.is_none());
+}
+
+#[test]
+fn test_cluster_method_names() {
+    assert_eq!(
+        ClusterMethod::from_name("Complete"),
+        Some(ClusterMethod::Complete)
+    );
+    assert_eq!(
+        ClusterMethod::from_name("connected-components"),
+        Some(ClusterMethod::Components)
+    );
+    assert_eq!(ClusterMethod::from_name("ward"), None);
+    assert_eq!(ClusterMethod::Average.name(), "average");
+}
@@ -0,0 +1,296 @@
+//! Tests for text extraction from the supported file formats
+
+use document_similarity_analyzer::extraction::{
+    detect_file_type, extract_text, extract_text_with_options, DetectionSource,
+    ExtractionOptions, FileType,
+};
+use document_similarity_analyzer::sentence::split_sentences;
+
+/// Read a file from `tests/fixtures`
+fn fixture(name: &str) -> Vec<u8> {
+    let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+    std::fs::read(&path).unwrap_or_else(|e| panic!("Failed to read fixture {}: {}", path, e))
+}
+
+#[test]
+fn test_detects_html_and_markdown_extensions() {
+    assert_eq!(FileType::from_filename("page.html"), Some(FileType::Html));
+    assert_eq!(FileType::from_filename("page.HTM"), Some(FileType::Html));
+    assert_eq!(FileType::from_filename("README.md"), Some(FileType::Markdown));
+    assert_eq!(FileType::from_filename("notes.markdown"), Some(FileType::Markdown));
+}
+
+#[test]
+fn test_html_strips_markup_scripts_and_styles() {
+    let html = r#"<!DOCTYPE html>
+<html>
+<head><title>Ignored title</title><style>p { color: red; }</style></head>
+<body>
+  <h1>Climate Change</h1>
+  <p>Global temperatures are <b>rising</b> quickly.</p>
+  <script>var tracking = "should not appear";</script>
+  <!-- a comment -->
+  <p>Caf&eacute; owners &amp; farmers are affected&hellip; &#8220;Act now&#x201D;</p>
+</body>
+</html>"#;
+
+    let text = extract_text(html.as_bytes(), FileType::Html).unwrap();
+
+    assert!(!text.contains("tracking"));
+    assert!(!text.contains("color: red"));
+    assert!(!text.contains("Ignored title"));
+    assert!(!text.contains("comment"));
+    assert!(text.contains("Global temperatures are rising quickly."));
+    assert!(text.contains("Café owners & farmers are affected… “Act now”"));
+
+    // The heading becomes its own sentence
+    let sentences = split_sentences(&text);
+    assert_eq!(sentences[0], "Climate Change");
+    assert_eq!(sentences[1], "Global temperatures are rising quickly.");
+}
+
+#[test]
+fn test_html_code_blocks_are_configurable() {
+    let html = "<p>Intro text.</p><pre>fn main() {}</pre><p>Outro text.</p>";
+
+    let stripped = extract_text(html.as_bytes(), FileType::Html).unwrap();
+    assert!(!stripped.contains("fn main"));
+
+    let options = ExtractionOptions { strip_code: false };
+    let kept = extract_text_with_options(html.as_bytes(), FileType::Html, &options).unwrap();
+    assert!(kept.contains("fn main() {}"));
+}
+
+#[test]
+fn test_markdown_strips_syntax_and_keeps_headings_as_sentences() {
+    let markdown = r#"---
+title: Front matter
+---
+# Project Overview
+
+This project uses **TF-IDF** and [cosine similarity](https://example.com) to compare documents
+written in _plain_ prose.
+
+```rust
+fn hidden() {}
+```
+
+## Features
+- Parallel processing with `rayon`
+- Sentence level matching
+
+| Format | Supported |
+| ------ | --------- |
+| PDF    | yes       |
+
+![Architecture diagram](diagram.png)
+"#;
+
+    let text = extract_text(markdown.as_bytes(), FileType::Markdown).unwrap();
+    let sentences = split_sentences(&text);
+
+    assert!(!text.contains("Front matter"));
+    assert!(!text.contains("hidden"));
+    assert!(!text.contains("**"));
+    assert!(!text.contains("https://example.com"));
+    assert!(!text.contains("------"));
+    assert_eq!(sentences[0], "Project Overview");
+    assert_eq!(
+        sentences[1],
+        "This project uses TF-IDF and cosine similarity to compare documents written in plain prose."
+    );
+    assert!(sentences.contains(&"Features".to_string()));
+    assert!(sentences.contains(&"Parallel processing with rayon".to_string()));
+    assert!(sentences.contains(&"Sentence level matching".to_string()));
+    assert!(sentences.contains(&"Architecture diagram".to_string()));
+}
+
+#[test]
+fn test_markdown_code_fences_are_configurable() {
+    let markdown = "Some text.\n\n```\nlet x = 1;\n```\n\nMore text.";
+
+    let stripped = extract_text(markdown.as_bytes(), FileType::Markdown).unwrap();
+    assert!(!stripped.contains("let x"));
+
+    let options = ExtractionOptions { strip_code: false };
+    let kept = extract_text_with_options(markdown.as_bytes(), FileType::Markdown, &options).unwrap();
+    assert!(kept.contains("let x = 1;"));
+}
+
+#[test]
+fn test_markdown_keeps_snake_case_identifiers() {
+    let markdown = "Call the compute_tf function first.";
+
+    let text = extract_text(markdown.as_bytes(), FileType::Markdown).unwrap();
+
+    assert_eq!(text, "Call the compute_tf function first.");
+}
+
+#[test]
+fn test_detects_office_extensions() {
+    assert_eq!(FileType::from_filename("essay.odt"), Some(FileType::Odt));
+    assert_eq!(FileType::from_filename("essay.RTF"), Some(FileType::Rtf));
+    assert_eq!(FileType::from_filename("slides.pptx"), Some(FileType::Pptx));
+    assert_eq!(FileType::from_filename("slides.odp"), Some(FileType::Odp));
+}
+
+#[test]
+fn test_odt_fixture() {
+    let text = extract_text(&fixture("sample.odt"), FileType::Odt).unwrap();
+
+    assert_eq!(
+        text,
+        "Renewable Energy\n\
+         Solar power is growing rapidly. Wind farms are expanding.\n\
+         Costs have fallen over the last decade.\n\
+         Storage remains the main challenge."
+    );
+}
+
+#[test]
+fn test_odt_huge_space_count_is_capped() {
+    // `text:c="4000000000"` must not allocate gigabytes of spaces
+    let text = extract_text(&fixture("huge_spaces.odt"), FileType::Odt).unwrap();
+
+    assert_eq!(text, "Spaced out words.\nOverflowing count.");
+}
+
+#[test]
+fn test_odp_fixture_skips_speaker_notes() {
+    let text = extract_text(&fixture("sample.odp"), FileType::Odp).unwrap();
+    let sentences = split_sentences(&text);
+
+    assert_eq!(
+        sentences,
+        vec![
+            "Machine Learning Basics",
+            "Supervised learning uses labels",
+            "Unsupervised learning finds structure",
+        ]
+    );
+}
+
+#[test]
+fn test_pptx_fixture_orders_slides_numerically() {
+    let text = extract_text(&fixture("sample.pptx"), FileType::Pptx).unwrap();
+    let sentences = split_sentences(&text);
+
+    assert_eq!(
+        sentences,
+        vec![
+            "Climate Change Overview",
+            "Global temperatures are rising",
+            "Sea levels are rising too",
+            "Questions and discussion",
+        ]
+    );
+}
+
+#[test]
+fn test_rtf_fixture() {
+    let text = extract_text(&fixture("sample.rtf"), FileType::Rtf).unwrap();
+
+    assert_eq!(
+        text,
+        "Artificial intelligence is transforming the world.\n\
+         Café owners use “smart” tools—every day.\n\
+         Braces { and } and backslash \\ are escaped.\n\
+         Unicode € sign.\n\
+         Neural networks are powerful."
+    );
+}
+
+#[test]
+fn test_invalid_packages_report_errors() {
+    assert!(extract_text(b"not a zip", FileType::Odt).is_err());
+    assert!(extract_text(b"not a zip", FileType::Pptx).is_err());
+    assert!(extract_text(b"plain text", FileType::Rtf).is_err());
+}
+
+#[test]
+fn test_detects_pdf_renamed_to_txt() {
+    let bytes = b"%PDF-1.7\n%\xe2\xe3\xcf\xd3\n1 0 obj\n<<>>\nendobj\n";
+
+    let detection = detect_file_type(bytes, "essay.txt", Some("text/plain")).unwrap();
+
+    assert_eq!(detection.file_type, FileType::Pdf);
+    assert_eq!(detection.source, DetectionSource::Content);
+    assert!(detection.mismatch.unwrap().contains("extension indicates TXT but content is PDF"));
+}
+
+#[test]
+fn test_pdf_header_must_start_the_file() {
+    let mention = b"Save the file as %PDF-1.7 before submitting it.";
+    let text = detect_file_type(mention, "notes.txt", None).unwrap();
+    assert_eq!(text.file_type, FileType::Txt);
+
+    let padded = b"\xEF\xBB\xBF\r\n  %PDF-1.4\n1 0 obj\n<<>>\nendobj\n";
+    let pdf = detect_file_type(padded, "upload", None).unwrap();
+    assert_eq!(pdf.file_type, FileType::Pdf);
+}
+
+#[test]
+fn test_detects_packages_without_extension() {
+    let docx = detect_file_type(&fixture("sample.docx"), "submission", None).unwrap();
+    assert_eq!(docx.file_type, FileType::Docx);
+    assert_eq!(docx.mismatch, None);
+
+    let odt = detect_file_type(&fixture("sample.odt"), "submission", None).unwrap();
+    assert_eq!(odt.file_type, FileType::Odt);
+
+    let pptx = detect_file_type(&fixture("sample.pptx"), "slides.docx", None).unwrap();
+    assert_eq!(pptx.file_type, FileType::Pptx);
+    assert!(pptx.mismatch.is_some());
+
+    let rtf = detect_file_type(&fixture("sample.rtf"), "essay.doc", None).unwrap();
+    assert_eq!(rtf.file_type, FileType::Rtf);
+}
+
+#[test]
+fn test_text_content_falls_back_to_extension_and_content_type() {
+    let markdown = detect_file_type(b"# Title\n\nBody.", "notes.md", Some("text/plain")).unwrap();
+    assert_eq!(markdown.file_type, FileType::Markdown);
+    assert_eq!(markdown.source, DetectionSource::Extension);
+
+    let html = detect_file_type(b"<p>Body.</p>", "upload", Some("text/html; charset=utf-8")).unwrap();
+    assert_eq!(html.file_type, FileType::Html);
+    assert_eq!(html.source, DetectionSource::ContentType);
+
+    let sniffed_html = detect_file_type(b"<!DOCTYPE html><p>Body.</p>", "upload", None).unwrap();
+    assert_eq!(sniffed_html.file_type, FileType::Html);
+
+    let plain = detect_file_type(b"Just some text.", "upload", None).unwrap();
+    assert_eq!(plain.file_type, FileType::Txt);
+    assert_eq!(plain.source, DetectionSource::Content);
+}
+
+#[test]
+fn test_unrecognized_binary_uses_extension_or_is_rejected() {
+    let binary = [0u8, 159, 146, 150, 0, 1];
+
+    let by_extension = detect_file_type(&binary, "broken.docx", None).unwrap();
+    assert_eq!(by_extension.file_type, FileType::Docx);
+    assert_eq!(by_extension.source, DetectionSource::Extension);
+
+    assert_eq!(detect_file_type(&binary, "image.bin", None), None);
+}
+
+#[test]
+fn test_txt_decodes_byte_order_marks() {
+    let utf8 = b"\xEF\xBB\xBFHello world.";
+    assert_eq!(extract_text(utf8, FileType::Txt).unwrap(), "Hello world.");
+
+    let utf16_le: Vec<u8> = b"\xFF\xFE"
+        .iter()
+        .copied()
+        .chain("Halo dunia.".encode_utf16().flat_map(|unit| unit.to_le_bytes()))
+        .collect();
+    assert_eq!(extract_text(&utf16_le, FileType::Txt).unwrap(), "Halo dunia.");
+
+    let utf16_be: Vec<u8> = b"\xFE\xFF"
+        .iter()
+        .copied()
+        .chain("Halo dunia.".encode_utf16().flat_map(|unit| unit.to_be_bytes()))
+        .collect();
+    assert_eq!(extract_text(&utf16_be, FileType::Txt).unwrap(), "Halo dunia.");
+}
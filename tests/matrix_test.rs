@@ -0,0 +1,76 @@
+//! Tests for the contiguous tiled similarity matrix
+
+use document_similarity_analyzer::core::{
+    compute_normalized_similarity_matrix, compute_similarity_matrix, compute_similarity_matrix_as,
+    cosine_similarity, f16, DenseMatrix,
+};
+
+/// Vectors with varied, partly overlapping non-negative entries
+fn sample_vectors(count: usize, dimensions: usize) -> Vec<Vec<f32>> {
+    (0..count)
+        .map(|row| {
+            (0..dimensions)
+                .map(|i| ((row * 31 + i * 7) as f32 * 0.37).sin().max(0.0))
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn test_matrix_spanning_several_tiles_matches_pairwise_cosine() {
+    // 150 rows: more than two 64-wide tiles, with a partial last tile
+    let vectors = sample_vectors(150, 24);
+    let matrix = compute_similarity_matrix(&vectors);
+
+    assert_eq!(matrix.size(), 150);
+    assert_eq!(matrix.as_slice().len(), 150 * 150);
+    for i in 0..150 {
+        assert_eq!(matrix.get(i, i), 1.0);
+        for j in (i + 1)..150 {
+            let expected = cosine_similarity(&vectors[i], &vectors[j]);
+            assert!((matrix.get(i, j) - expected).abs() < 1e-5, "[{i}][{j}]");
+            assert_eq!(
+                matrix.get(i, j),
+                matrix.get(j, i),
+                "[{i}][{j}] not mirrored"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_rows_are_contiguous_and_convert_to_nested_vectors() {
+    let vectors = vec![vec![1.0, 0.0], vec![0.0, 1.0], vec![1.0, 1.0]];
+    let matrix = compute_similarity_matrix(&vectors);
+    let rows = matrix.to_rows();
+
+    assert_eq!(rows.len(), 3);
+    assert_eq!(matrix.row(1), rows[1].as_slice());
+    assert_eq!(rows[0][1], 0.0);
+    assert!((rows[0][2] - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+}
+
+#[test]
+fn test_half_precision_output_stays_close_to_f32() {
+    let vectors = sample_vectors(70, 16);
+    let full = compute_similarity_matrix(&vectors);
+    let half: DenseMatrix<f16> = compute_similarity_matrix_as(&vectors);
+
+    assert_eq!(half.size(), full.size());
+    assert_eq!(half.get(3, 3), 1.0);
+    for i in 0..70 {
+        for j in 0..70 {
+            assert!((half.get(i, j) - full.get(i, j)).abs() < 1e-3, "[{i}][{j}]");
+        }
+    }
+}
+
+#[test]
+fn test_empty_and_single_vector_matrices() {
+    let empty = compute_similarity_matrix(&[]);
+    assert_eq!(empty.size(), 0);
+    assert!(empty.to_rows().is_empty());
+
+    let single: DenseMatrix<f32> = compute_normalized_similarity_matrix(&[vec![1.0, 0.0]]);
+    assert_eq!(single.to_rows(), vec![vec![1.0]]);
+}
@@ -0,0 +1,245 @@
+//! Tests for the analysis memory estimate and budget
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use document_similarity_analyzer::core::{
+    analyze_sentence_similarity_streaming_within_budget, analyze_sentence_similarity_with_options,
+    analyze_sentence_similarity_within_budget, estimate_analysis_memory, AlignmentMode,
+    MemoryBudgetExceeded, SentenceAnalysisOptions, SentenceDocument,
+};
+use document_similarity_analyzer::models::{AggregationStrategy, ClusterMethod};
+
+fn documents(count: usize, sentences: usize) -> Vec<SentenceDocument> {
+    (0..count)
+        .map(|doc| {
+            let sentences = (0..sentences)
+                .map(|i| {
+                    format!(
+                        "Sentence number {} of document {} talks about rivers.",
+                        i, doc
+                    )
+                })
+                .collect();
+            SentenceDocument::new(format!("doc{}.txt", doc), sentences)
+        })
+        .collect()
+}
+
+#[test]
+fn test_estimate_grows_with_input() {
+    let options = SentenceAnalysisOptions::default();
+    let small = estimate_analysis_memory(&documents(2, 10), &options);
+    let large = estimate_analysis_memory(&documents(2, 100), &options);
+
+    assert!(small.text > 0 && small.tokens > small.text);
+    assert!(large.text > small.text * 9);
+    // Similarity blocks grow with the product of the sentence counts
+    assert!(large.similarity_blocks >= small.similarity_blocks * 100);
+    assert_eq!(small.matrices, 0);
+    assert_eq!(small.alignment, 0);
+    assert_eq!(
+        small.total(),
+        small.text
+            + small.tokens
+            + small.vectors
+            + small.similarity_blocks
+            + small.alignment
+            + small.matrices
+    );
+}
+
+#[test]
+fn test_matrices_are_counted_when_requested() {
+    let options = SentenceAnalysisOptions {
+        clustering: Some(ClusterMethod::Average),
+        ..SentenceAnalysisOptions::default()
+    };
+
+    let estimate = estimate_analysis_memory(&documents(4, 5), &options);
+    assert_eq!(estimate.matrices, 3 * 4 * 4 * std::mem::size_of::<f32>());
+}
+
+#[test]
+fn test_alignment_buffers_are_counted_when_requested() {
+    let documents = documents(2, 100);
+    let estimate =
+        |options: SentenceAnalysisOptions| estimate_analysis_memory(&documents, &options);
+
+    let monotonic = estimate(SentenceAnalysisOptions {
+        alignment: AlignmentMode::Monotonic,
+        ..SentenceAnalysisOptions::default()
+    });
+    assert_eq!(monotonic.alignment, 101 * 101 * std::mem::size_of::<f32>());
+
+    let bipartite = estimate(SentenceAnalysisOptions {
+        alignment: AlignmentMode::Bipartite,
+        ..SentenceAnalysisOptions::default()
+    });
+    assert!(bipartite.alignment > 0);
+
+    // Greedy alignment sorts every cell of the block
+    let aggregated = estimate(SentenceAnalysisOptions {
+        aggregation: AggregationStrategy::Alignment,
+        ..SentenceAnalysisOptions::default()
+    });
+    assert_eq!(
+        aggregated.alignment,
+        100 * 100 * std::mem::size_of::<(usize, usize)>()
+    );
+    assert!(aggregated.total() > estimate(SentenceAnalysisOptions::default()).total());
+}
+
+#[test]
+fn test_budget_check_reports_estimate_and_budget() {
+    let estimate = estimate_analysis_memory(&documents(3, 50), &SentenceAnalysisOptions::default());
+
+    assert!(estimate.check(estimate.total()).is_ok());
+
+    let error = estimate.check(estimate.total() - 1).unwrap_err();
+    assert_eq!(
+        error,
+        MemoryBudgetExceeded {
+            estimated: estimate.total(),
+            budget: estimate.total() - 1
+        }
+    );
+
+    let message = MemoryBudgetExceeded {
+        estimated: 3 * 1024 * 1024,
+        budget: 2 * 1024 * 1024,
+    }
+    .to_string();
+    assert_eq!(
+        message,
+        "analysis needs an estimated 3.0 MB, over the memory budget of 2.0 MB"
+    );
+}
+
+#[test]
+fn test_single_document_has_no_similarity_blocks() {
+    let estimate = estimate_analysis_memory(&documents(1, 20), &SentenceAnalysisOptions::default());
+    assert_eq!(estimate.similarity_blocks, 0);
+}
+
+#[test]
+fn test_cjk_tokens_are_counted_as_bigrams() {
+    let document = |sentence: &str| {
+        vec![SentenceDocument::new(
+            "doc.txt".to_string(),
+            vec![sentence.to_string()],
+        )]
+    };
+    let options = SentenceAnalysisOptions::default();
+
+    // Both are a single whitespace-separated word
+    let long = estimate_analysis_memory(&document("今天天气很好"), &options);
+    let short = estimate_analysis_memory(&document("今天"), &options);
+    assert!(long.vectors > short.vectors);
+}
+
+#[test]
+fn test_budget_allows_analysis_that_fits() {
+    let documents = documents(2, 20);
+    let options = SentenceAnalysisOptions {
+        threshold: 0.1,
+        ..SentenceAnalysisOptions::default()
+    };
+
+    let analysis =
+        analyze_sentence_similarity_within_budget(&documents, &options, usize::MAX).unwrap();
+    let unbudgeted = analyze_sentence_similarity_with_options(&documents, &options);
+    assert!(!analysis.matches.is_empty());
+    assert_eq!(analysis.matches.len(), unbudgeted.matches.len());
+}
+
+#[test]
+fn test_budget_stops_analysis_when_matches_exceed_it() {
+    let documents = documents(2, 100);
+    let options = SentenceAnalysisOptions {
+        threshold: 0.1,
+        ..SentenceAnalysisOptions::default()
+    };
+    let budget = estimate_analysis_memory(&documents, &options).total() + 64 * 1024;
+
+    let error =
+        analyze_sentence_similarity_within_budget(&documents, &options, budget).unwrap_err();
+    assert_eq!(error.budget, budget);
+    assert!(error.estimated > budget);
+
+    // A page keeps only `limit` matches, so the same budget is enough
+    let limited = SentenceAnalysisOptions {
+        limit: Some(10),
+        ..options
+    };
+    let analysis = analyze_sentence_similarity_within_budget(&documents, &limited, budget).unwrap();
+    assert_eq!(analysis.matches.len(), 10);
+}
+
+#[test]
+fn test_budget_stops_within_a_single_document_pair() {
+    let documents = documents(2, 300);
+    let options = SentenceAnalysisOptions {
+        threshold: 0.0,
+        ..SentenceAnalysisOptions::default()
+    };
+    let budget = estimate_analysis_memory(&documents, &options).total() + 64 * 1024;
+
+    // 90,000 matches need far more than 64 KB; the budget is charged per match
+    let error =
+        analyze_sentence_similarity_within_budget(&documents, &options, budget).unwrap_err();
+    assert!(error.estimated > budget);
+    assert!(error.estimated < budget + 1024 * 1024);
+
+    // Only the kept page is copied, however many matches the pair has
+    let limited = SentenceAnalysisOptions {
+        limit: Some(1),
+        ..options
+    };
+    let analysis = analyze_sentence_similarity_within_budget(&documents, &limited, budget).unwrap();
+    assert_eq!(analysis.matches.len(), 1);
+    assert_eq!(analysis.total_matches, 300 * 300);
+}
+
+#[test]
+fn test_streaming_budget_is_checked_before_any_match() {
+    let documents = documents(2, 10);
+    let options = SentenceAnalysisOptions::default();
+    let estimate = estimate_analysis_memory(&documents, &options);
+
+    let streamed = AtomicUsize::new(0);
+    let on_match = |_| {
+        streamed.fetch_add(1, Ordering::Relaxed);
+    };
+    let error = analyze_sentence_similarity_streaming_within_budget(
+        &documents,
+        &options,
+        estimate.total() - 1,
+        on_match,
+        || false,
+    )
+    .unwrap_err();
+    assert_eq!(error.estimated, estimate.total());
+    assert_eq!(streamed.load(Ordering::Relaxed), 0);
+
+    let analysis = analyze_sentence_similarity_streaming_within_budget(
+        &documents,
+        &options,
+        estimate.total(),
+        on_match,
+        || false,
+    )
+    .unwrap();
+    assert_eq!(analysis.total_matches, streamed.load(Ordering::Relaxed));
+}
+
+#[test]
+fn test_budget_refuses_analysis_over_the_estimate() {
+    let documents = documents(2, 10);
+    let options = SentenceAnalysisOptions::default();
+    let estimate = estimate_analysis_memory(&documents, &options);
+
+    let error =
+        analyze_sentence_similarity_within_budget(&documents, &options, estimate.total() - 1)
+            .unwrap_err();
+    assert_eq!(error.estimated, estimate.total());
+}
@@ -0,0 +1,895 @@
+//! Tests for sentence-level analysis options
+
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use document_similarity_analyzer::core::{
+    analyze_sentence_similarity_streaming, analyze_sentence_similarity_streaming_until,
+    analyze_sentence_similarity_with_options, AlignmentMode, QuoteMode, SentenceAnalysisOptions,
+    SentenceDocument,
+};
+use document_similarity_analyzer::models::{
+    descending_similarity, AggregationStrategy, AnalysisStreamRecord, MatchCursor, SentenceMatch,
+};
+
+fn document(name: &str, sentences: &[&str]) -> SentenceDocument {
+    SentenceDocument::new(
+        name.to_string(),
+        sentences.iter().map(|s| s.to_string()).collect(),
+    )
+}
+
+#[test]
+fn test_short_sentences_are_excluded_from_matching() {
+    let documents = vec![
+        document("a.txt", &["Introduction.", "Neural networks learn useful representations."]),
+        document("b.txt", &["Introduction.", "Neural networks learn useful representations."]),
+    ];
+
+    let analysis = analyze_sentence_similarity_with_options(&documents, &SentenceAnalysisOptions::default());
+
+    assert_eq!(analysis.filtered_sentences, 2);
+    assert_eq!(analysis.matches.len(), 1);
+    // Filtered sentences keep their place in the index bookkeeping
+    assert_eq!(analysis.matches[0].source_sentence_index, 1);
+    assert_eq!(analysis.matches[0].target_sentence_index, 1);
+}
+
+#[test]
+fn test_min_chars_filters_sentences() {
+    let documents = vec![
+        document("a.txt", &["Table 1 shows results.", "The model converges after ten epochs."]),
+        document("b.txt", &["Table 1 shows results.", "The model converges after ten epochs."]),
+    ];
+    let options = SentenceAnalysisOptions {
+        min_chars: 25,
+        ..SentenceAnalysisOptions::default()
+    };
+
+    let analysis = analyze_sentence_similarity_with_options(&documents, &options);
+
+    assert_eq!(analysis.filtered_sentences, 2);
+    assert_eq!(analysis.matches.len(), 1);
+}
+
+#[test]
+fn test_filtering_can_be_disabled() {
+    let documents = vec![
+        document("a.txt", &["Introduction."]),
+        document("b.txt", &["Introduction."]),
+    ];
+    let options = SentenceAnalysisOptions {
+        min_tokens: 0,
+        ..SentenceAnalysisOptions::default()
+    };
+
+    let analysis = analyze_sentence_similarity_with_options(&documents, &options);
+
+    assert_eq!(analysis.filtered_sentences, 0);
+    assert_eq!(analysis.matches.len(), 1);
+}
+
+fn quoting_documents() -> Vec<SentenceDocument> {
+    vec![
+        document(
+            "a.txt",
+            &[
+                "“Neural networks learn useful representations from raw data.”",
+                "Gradient descent minimizes the training loss efficiently (Smith, 2020).",
+                "Our own experiments confirm the earlier findings clearly.",
+            ],
+        ),
+        document(
+            "b.txt",
+            &[
+                "Neural networks learn useful representations from raw data.",
+                "Gradient descent minimizes the training loss efficiently.",
+                "Our own experiments confirm the earlier findings clearly.",
+            ],
+        ),
+    ]
+}
+
+#[test]
+fn test_quote_mode_mark_flags_matches() {
+    let options = SentenceAnalysisOptions {
+        quotes: QuoteMode::Mark,
+        ..SentenceAnalysisOptions::default()
+    };
+
+    let analysis = analyze_sentence_similarity_with_options(&quoting_documents(), &options);
+
+    assert_eq!(analysis.quoted_sentences, 2);
+    assert_eq!(analysis.matches.len(), 3);
+    let mut flags: Vec<(usize, bool, bool)> = analysis
+        .matches
+        .iter()
+        .map(|m| (m.source_sentence_index, m.quoted, m.cited))
+        .collect();
+    flags.sort();
+    assert_eq!(flags, vec![(0, true, false), (1, false, true), (2, false, false)]);
+}
+
+#[test]
+fn test_quote_mode_exclude_drops_attributed_sentences() {
+    let options = SentenceAnalysisOptions {
+        quotes: QuoteMode::Exclude,
+        ..SentenceAnalysisOptions::default()
+    };
+
+    let analysis = analyze_sentence_similarity_with_options(&quoting_documents(), &options);
+
+    assert_eq!(analysis.matches.len(), 1);
+    assert_eq!(analysis.matches[0].source_sentence_index, 2);
+}
+
+#[test]
+fn test_quote_mode_names() {
+    assert_eq!(QuoteMode::from_name("Exclude"), Some(QuoteMode::Exclude));
+    assert_eq!(QuoteMode::from_name("off"), Some(QuoteMode::Off));
+    assert_eq!(QuoteMode::from_name("maybe"), None);
+}
+
+fn papers_with_references() -> Vec<SentenceDocument> {
+    let entry = "Smith, J. and Lee, K. Deep learning for text similarity. Journal of AI, 2020.";
+    vec![
+        document(
+            "a.txt",
+            &[
+                "Our method segments documents into sentences.",
+                "It scores them with cosine similarity.",
+                "References",
+                entry,
+            ],
+        ),
+        document(
+            "b.txt",
+            &[
+                "Photosynthesis converts light into chemical energy.",
+                "Plants store it as glucose molecules.",
+                "Bibliography",
+                entry,
+            ],
+        ),
+    ]
+}
+
+#[test]
+fn test_reference_sections_are_excluded_by_default() {
+    let analysis = analyze_sentence_similarity_with_options(
+        &papers_with_references(),
+        &SentenceAnalysisOptions::default(),
+    );
+
+    // The one-word headings are already counted as too short
+    assert_eq!(analysis.filtered_sentences, 2);
+    assert_eq!(analysis.reference_sentences, 2);
+    assert!(analysis.matches.is_empty());
+}
+
+#[test]
+fn test_reference_sections_can_be_kept() {
+    let options = SentenceAnalysisOptions {
+        include_references: true,
+        ..SentenceAnalysisOptions::default()
+    };
+
+    let analysis = analyze_sentence_similarity_with_options(&papers_with_references(), &options);
+
+    assert_eq!(analysis.reference_sentences, 0);
+    assert_eq!(analysis.matches.len(), 1);
+    assert_eq!(analysis.matches[0].source_sentence_index, 3);
+}
+
+#[test]
+fn test_template_sentences_are_suppressed_per_document() {
+    let documents = vec![
+        document(
+            "a.txt",
+            &[
+                "Explain how gradient descent trains a neural network.",
+                "Gradient descent follows the negative gradient of the loss.",
+            ],
+        ),
+        document(
+            "b.txt",
+            &[
+                "Explain how gradient descent trains a neural network.",
+                "Gradient descent follows the negative gradient of the loss.",
+                "Learning rates control the step size.",
+            ],
+        ),
+    ];
+    let options = SentenceAnalysisOptions {
+        template: vec!["Explain how gradient descent trains a neural network.".to_string()],
+        ..SentenceAnalysisOptions::default()
+    };
+
+    let analysis = analyze_sentence_similarity_with_options(&documents, &options);
+
+    assert_eq!(analysis.matches.len(), 1);
+    assert_eq!(analysis.matches[0].source_sentence_index, 1);
+    let counts: Vec<(usize, usize)> = analysis
+        .documents
+        .iter()
+        .map(|d| (d.total_sentences, d.template_sentences))
+        .collect();
+    assert_eq!(counts, vec![(2, 1), (3, 1)]);
+}
+
+#[test]
+fn test_common_boilerplate_is_reported_instead_of_matched() {
+    let disclaimer = "This work is my own and all sources are acknowledged.";
+    let documents = vec![
+        document(
+            "a.txt",
+            &[
+                disclaimer,
+                "Rust guarantees memory safety without garbage collection.",
+            ],
+        ),
+        document(
+            "b.txt",
+            &[
+                disclaimer,
+                "Rust guarantees memory safety without garbage collection.",
+            ],
+        ),
+        document(
+            "c.txt",
+            &[
+                "THIS WORK IS MY OWN, AND ALL SOURCES ARE ACKNOWLEDGED!",
+                "Photosynthesis happens in chloroplasts.",
+            ],
+        ),
+        document(
+            "d.txt",
+            &[
+                "Volcanoes form at tectonic plate boundaries.",
+                "Lava cools into igneous rock.",
+            ],
+        ),
+    ];
+    let options = SentenceAnalysisOptions {
+        boilerplate_fraction: Some(0.5),
+        ..SentenceAnalysisOptions::default()
+    };
+
+    let analysis = analyze_sentence_similarity_with_options(&documents, &options);
+
+    assert_eq!(analysis.common_boilerplate.len(), 1);
+    assert_eq!(analysis.common_boilerplate[0].sentence, disclaimer);
+    assert_eq!(analysis.common_boilerplate[0].documents, 3);
+    assert_eq!(analysis.matches.len(), 1);
+    assert_eq!(analysis.matches[0].source_sentence_index, 1);
+}
+
+#[test]
+fn test_sentence_shared_by_two_documents_is_not_boilerplate() {
+    let documents = vec![
+        document(
+            "a.txt",
+            &["Rust guarantees memory safety without garbage collection."],
+        ),
+        document(
+            "b.txt",
+            &["Rust guarantees memory safety without garbage collection."],
+        ),
+    ];
+    let options = SentenceAnalysisOptions {
+        boilerplate_fraction: Some(0.1),
+        ..SentenceAnalysisOptions::default()
+    };
+
+    let analysis = analyze_sentence_similarity_with_options(&documents, &options);
+
+    assert!(analysis.common_boilerplate.is_empty());
+    assert_eq!(analysis.matches.len(), 1);
+}
+
+#[test]
+fn test_directional_coverage_of_copied_essay() {
+    let copied = [
+        "Glaciers carve deep valleys over thousands of years.",
+        "Meltwater deposits sediment far downstream.",
+    ];
+    let long: Vec<&str> = copied
+        .iter()
+        .copied()
+        .chain([
+            "Volcanoes release gases that change the climate.",
+            "Coral reefs protect coastlines from storm surges.",
+            "Deserts receive less than ten inches of rain yearly.",
+            "Rivers meander across wide floodplains slowly.",
+        ])
+        .collect();
+    let documents = vec![document("short.txt", &copied), document("long.txt", &long)];
+
+    let analysis =
+        analyze_sentence_similarity_with_options(&documents, &SentenceAnalysisOptions::default());
+
+    let global = &analysis.global_similarity[0];
+    assert_eq!(global.coverage_a.sentences, 1.0);
+    assert_eq!(global.coverage_a.tokens, 1.0);
+    assert!((global.coverage_a.best_match_mean - 1.0).abs() < 1e-5);
+    assert!((global.coverage_b.sentences - 2.0 / 6.0).abs() < 1e-6);
+    assert!(global.coverage_b.tokens < 0.5);
+    assert!(global.score < 0.5);
+
+    let json = serde_json::to_value(global.coverage_a).unwrap();
+    assert!(json.get("best_match_mean").is_some());
+}
+
+fn analyze_with(documents: &[SentenceDocument], aggregation: AggregationStrategy) -> f32 {
+    let options = SentenceAnalysisOptions {
+        aggregation,
+        ..SentenceAnalysisOptions::default()
+    };
+    let analysis = analyze_sentence_similarity_with_options(documents, &options);
+    assert_eq!(analysis.global_similarity[0].strategy, aggregation);
+    analysis.global_similarity[0].score
+}
+
+#[test]
+fn test_aggregation_strategies_on_identical_documents() {
+    let sentences = [
+        "Glaciers carve deep valleys over thousands of years.",
+        "Volcanoes release gases that change the climate.",
+        "Coral reefs protect coastlines from storm surges.",
+    ];
+    let documents = vec![document("a.txt", &sentences), document("b.txt", &sentences)];
+
+    let mean = analyze_with(&documents, AggregationStrategy::Mean);
+    let best = analyze_with(&documents, AggregationStrategy::BestMatch);
+    let aligned = analyze_with(&documents, AggregationStrategy::Alignment);
+    let whole = analyze_with(&documents, AggregationStrategy::Document);
+
+    // The all-pairs mean is diluted by unrelated sentence pairs
+    assert!(mean < 0.5);
+    assert!((best - 1.0).abs() < 1e-5);
+    assert!((aligned - 1.0).abs() < 1e-5);
+    assert!((whole - 1.0).abs() < 1e-5);
+}
+
+#[test]
+fn test_alignment_counts_each_sentence_once() {
+    let documents = vec![
+        document(
+            "a.txt",
+            &[
+                "Glaciers carve deep valleys over thousands of years.",
+                "Glaciers carve deep valleys over thousands of years.",
+            ],
+        ),
+        document(
+            "b.txt",
+            &[
+                "Glaciers carve deep valleys over thousands of years.",
+                "Volcanoes release gases that change the climate.",
+            ],
+        ),
+    ];
+
+    let best = analyze_with(&documents, AggregationStrategy::BestMatch);
+    let aligned = analyze_with(&documents, AggregationStrategy::Alignment);
+
+    assert!((best - 0.75).abs() < 1e-5);
+    assert!((aligned - 0.5).abs() < 1e-5);
+}
+
+#[test]
+fn test_aggregation_strategy_names() {
+    assert_eq!(
+        AggregationStrategy::from_name("best-match"),
+        Some(AggregationStrategy::BestMatch)
+    );
+    assert_eq!(
+        AggregationStrategy::from_name("DOCUMENT"),
+        Some(AggregationStrategy::Document)
+    );
+    assert_eq!(AggregationStrategy::from_name("median"), None);
+}
+
+const GLACIERS: &str = "Glaciers carve deep valleys over thousands of years.";
+const VOLCANOES: &str = "Volcanoes release gases that change the climate.";
+
+fn matches_with(documents: &[SentenceDocument], alignment: AlignmentMode) -> Vec<(usize, usize)> {
+    let options = SentenceAnalysisOptions {
+        alignment,
+        ..SentenceAnalysisOptions::default()
+    };
+    let mut pairs: Vec<(usize, usize)> =
+        analyze_sentence_similarity_with_options(documents, &options)
+            .matches
+            .iter()
+            .map(|m| (m.source_sentence_index, m.target_sentence_index))
+            .collect();
+    pairs.sort();
+    pairs
+}
+
+#[test]
+fn test_bipartite_alignment_matches_each_sentence_once() {
+    let documents = vec![
+        document("a.txt", &[GLACIERS, VOLCANOES]),
+        document("b.txt", &[GLACIERS, GLACIERS, VOLCANOES]),
+    ];
+
+    assert_eq!(
+        matches_with(&documents, AlignmentMode::Off),
+        vec![(0, 0), (0, 1), (1, 2)]
+    );
+    let aligned = matches_with(&documents, AlignmentMode::Bipartite);
+    assert_eq!(aligned.len(), 2);
+    assert!(aligned.contains(&(1, 2)));
+}
+
+#[test]
+fn test_monotonic_alignment_preserves_order() {
+    let documents = vec![
+        document("a.txt", &[GLACIERS, VOLCANOES]),
+        document("b.txt", &[VOLCANOES, GLACIERS]),
+    ];
+
+    assert_eq!(
+        matches_with(&documents, AlignmentMode::Bipartite),
+        vec![(0, 1), (1, 0)]
+    );
+    assert_eq!(matches_with(&documents, AlignmentMode::Monotonic).len(), 1);
+}
+
+#[test]
+fn test_bipartite_alignment_with_more_rows_than_columns() {
+    let documents = vec![
+        document("a.txt", &[VOLCANOES, GLACIERS, GLACIERS]),
+        document("b.txt", &[GLACIERS]),
+    ];
+
+    let aligned = matches_with(&documents, AlignmentMode::Bipartite);
+    assert_eq!(aligned.len(), 1);
+    assert_eq!(aligned[0].1, 0);
+}
+
+/// Pairs among the first two sentences of each document after aligning them
+/// at a low threshold, with `padding` sentences appended to both documents
+fn aligned_with_padding(padding: &[(String, String)]) -> Vec<(usize, usize)> {
+    // Greedy takes (0, 0) first; the best assignment is (0, 1) and (1, 0)
+    let mut a = vec![
+        "Harbor cranes lift rusty orchard bees.".to_string(),
+        "Containers freight ships dock port storm.".to_string(),
+    ];
+    let mut b = vec![
+        "Harbor cranes lift rusty containers freight ships.".to_string(),
+        "Orchard bees meadow sunny glade spring.".to_string(),
+    ];
+    for (source, target) in padding {
+        a.push(source.clone());
+        b.push(target.clone());
+    }
+    let documents = vec![
+        SentenceDocument::new("a.txt".to_string(), a),
+        SentenceDocument::new("b.txt".to_string(), b),
+    ];
+    let options = SentenceAnalysisOptions {
+        threshold: 0.2,
+        alignment: AlignmentMode::Bipartite,
+        ..SentenceAnalysisOptions::default()
+    };
+
+    let mut pairs: Vec<(usize, usize)> =
+        analyze_sentence_similarity_with_options(&documents, &options)
+            .matches
+            .iter()
+            .map(|m| (m.source_sentence_index, m.target_sentence_index))
+            .filter(|&(source, target)| source < 2 && target < 2)
+            .collect();
+    pairs.sort();
+    pairs
+}
+
+#[test]
+fn test_bipartite_alignment_ignores_sentences_without_matches() {
+    assert_eq!(aligned_with_padding(&[]), vec![(0, 1), (1, 0)]);
+
+    // Unmatched sentences do not count towards the exact assignment size
+    let unmatched: Vec<(String, String)> = (0..600)
+        .map(|i| {
+            (
+                format!("Source{i} alpha{i} bravo{i} charlie{i} delta{i}."),
+                format!("Target{i} echo{i} foxtrot{i} golf{i} hotel{i}."),
+            )
+        })
+        .collect();
+    assert_eq!(aligned_with_padding(&unmatched), vec![(0, 1), (1, 0)]);
+}
+
+#[test]
+fn test_bipartite_alignment_falls_back_to_greedy_for_large_blocks() {
+    let copied: Vec<(String, String)> = (0..600)
+        .map(|i| {
+            let sentence = format!("Word{i} alpha{i} bravo{i} charlie{i} delta{i}.");
+            (sentence.clone(), sentence)
+        })
+        .collect();
+
+    assert_eq!(aligned_with_padding(&copied), vec![(0, 0)]);
+}
+
+fn overlapping_documents() -> Vec<SentenceDocument> {
+    vec![
+        document(
+            "a.txt",
+            &[
+                "Glaciers carve deep valleys over thousands of years.",
+                "Rivers meander slowly across wide floodplains.",
+                "Deserts receive very little rain each year.",
+            ],
+        ),
+        document(
+            "b.txt",
+            &[
+                "Glaciers carve deep valleys over thousands of years.",
+                "Rivers meander slowly across wide green floodplains.",
+            ],
+        ),
+        document(
+            "c.txt",
+            &[
+                "Deserts receive very little rain each year.",
+                "Glaciers carve deep valleys over many thousands of years.",
+            ],
+        ),
+    ]
+}
+
+#[test]
+fn test_streaming_emits_the_same_matches() {
+    let documents = overlapping_documents();
+    let options = SentenceAnalysisOptions {
+        threshold: 0.5,
+        ..SentenceAnalysisOptions::default()
+    };
+
+    let collected = analyze_sentence_similarity_with_options(&documents, &options);
+    let streamed = Mutex::new(Vec::new());
+    let analysis = analyze_sentence_similarity_streaming(&documents, &options, |m| {
+        streamed.lock().unwrap().push(m)
+    });
+
+    let key = |m: &SentenceMatch| {
+        (
+            m.source_doc.clone(),
+            m.source_sentence_index,
+            m.target_doc.clone(),
+            m.target_sentence_index,
+        )
+    };
+    let mut expected: Vec<_> = collected.matches.iter().map(key).collect();
+    let mut actual: Vec<_> = streamed.into_inner().unwrap().iter().map(key).collect();
+    expected.sort();
+    actual.sort();
+
+    assert!(analysis.matches.is_empty());
+    assert_eq!(analysis.total_matches, collected.matches.len());
+    assert_eq!(actual, expected);
+    assert_eq!(
+        analysis.global_similarity.len(),
+        collected.global_similarity.len()
+    );
+}
+
+#[test]
+fn test_cancelled_streaming_skips_remaining_pairs() {
+    let documents = overlapping_documents();
+    let paged = SentenceAnalysisOptions {
+        threshold: 0.5,
+        limit: Some(2),
+        ..SentenceAnalysisOptions::default()
+    };
+
+    for options in [SentenceAnalysisOptions::default(), paged] {
+        let streamed = Mutex::new(Vec::new());
+        let analysis = analyze_sentence_similarity_streaming_until(
+            &documents,
+            &options,
+            |m| streamed.lock().unwrap().push(m),
+            || true,
+        );
+
+        assert!(streamed.into_inner().unwrap().is_empty());
+        assert_eq!(analysis.total_matches, 0);
+        assert!(analysis.global_similarity.is_empty());
+    }
+}
+
+#[test]
+fn test_cancelled_streaming_stops_within_a_document_pair() {
+    let sentences: Vec<String> = (0..60)
+        .map(|i| format!("Rivers carry sediment number {} to the sea.", i))
+        .collect();
+    let documents = vec![
+        SentenceDocument::new("a.txt".to_string(), sentences.clone()),
+        SentenceDocument::new("b.txt".to_string(), sentences),
+    ];
+    let options = SentenceAnalysisOptions {
+        threshold: 0.0,
+        ..SentenceAnalysisOptions::default()
+    };
+
+    let streamed = Mutex::new(0);
+    let analysis = analyze_sentence_similarity_streaming_until(
+        &documents,
+        &options,
+        |_| *streamed.lock().unwrap() += 1,
+        || *streamed.lock().unwrap() > 0,
+    );
+
+    // The single pair has 3600 matches; cancelling stops it after a batch
+    let streamed = streamed.into_inner().unwrap();
+    assert!(streamed > 0 && streamed < 60 * 60);
+    assert_eq!(analysis.total_matches, streamed);
+}
+
+#[test]
+fn test_stream_error_record() {
+    let record = AnalysisStreamRecord::Error {
+        message: "analysis failed before completing".to_string(),
+    };
+
+    assert_eq!(
+        serde_json::to_string(&record).unwrap(),
+        r#"{"type":"error","message":"analysis failed before completing"}"#
+    );
+}
+
+#[test]
+fn test_limit_keeps_the_best_matches() {
+    let documents = overlapping_documents();
+    let all = analyze_sentence_similarity_with_options(
+        &documents,
+        &SentenceAnalysisOptions {
+            threshold: 0.5,
+            ..SentenceAnalysisOptions::default()
+        },
+    );
+    let options = SentenceAnalysisOptions {
+        threshold: 0.5,
+        limit: Some(2),
+        ..SentenceAnalysisOptions::default()
+    };
+    let top = analyze_sentence_similarity_with_options(&documents, &options);
+
+    assert!(all.matches.len() > 2);
+    assert_eq!(top.total_matches, all.matches.len());
+    assert_eq!(top.matches.len(), 2);
+    assert_eq!(top.matches[0].similarity, all.matches[0].similarity);
+    assert_eq!(top.matches[1].similarity, all.matches[1].similarity);
+
+    // Streamed top-K matches arrive best first
+    let streamed = Mutex::new(Vec::new());
+    analyze_sentence_similarity_streaming(&documents, &options, |m| {
+        streamed.lock().unwrap().push(m.similarity)
+    });
+    let streamed = streamed.into_inner().unwrap();
+    assert_eq!(streamed.len(), 2);
+    assert!(streamed[0] >= streamed[1]);
+}
+
+fn page_key(m: &SentenceMatch) -> (String, usize, String, usize) {
+    (
+        m.source_doc.clone(),
+        m.source_sentence_index,
+        m.target_doc.clone(),
+        m.target_sentence_index,
+    )
+}
+
+#[test]
+fn test_offset_and_cursor_pages_cover_all_matches() {
+    let documents = overlapping_documents();
+    let options = SentenceAnalysisOptions {
+        threshold: 0.1,
+        ..SentenceAnalysisOptions::default()
+    };
+    let all = analyze_sentence_similarity_with_options(&documents, &options);
+    assert!(all.matches.len() >= 4);
+    assert!(all.next_cursor.is_none());
+
+    // Offset paging
+    let by_offset: Vec<_> = (0..all.matches.len())
+        .step_by(2)
+        .flat_map(|offset| {
+            let page = analyze_sentence_similarity_with_options(
+                &documents,
+                &SentenceAnalysisOptions {
+                    limit: Some(2),
+                    offset,
+                    ..options.clone()
+                },
+            );
+            page.matches
+        })
+        .map(|m| page_key(&m))
+        .collect();
+
+    // Cursor paging
+    let mut by_cursor = Vec::new();
+    let mut cursor = None;
+    loop {
+        let page = analyze_sentence_similarity_with_options(
+            &documents,
+            &SentenceAnalysisOptions {
+                limit: Some(2),
+                cursor,
+                ..options.clone()
+            },
+        );
+        assert_eq!(page.total_matches, all.matches.len());
+        by_cursor.extend(page.matches.iter().map(page_key));
+        match page.next_cursor {
+            Some(next) => cursor = Some(MatchCursor::decode(&next.encode()).unwrap()),
+            None => break,
+        }
+    }
+
+    let expected: Vec<_> = all.matches.iter().map(page_key).collect();
+    assert_eq!(by_offset, expected);
+    assert_eq!(by_cursor, expected);
+}
+
+#[test]
+fn test_per_document_pair_limit() {
+    let documents = overlapping_documents();
+    let unlimited = SentenceAnalysisOptions {
+        threshold: 0.1,
+        ..SentenceAnalysisOptions::default()
+    };
+    let options = SentenceAnalysisOptions {
+        per_document_pair_limit: Some(1),
+        ..unlimited.clone()
+    };
+    let all = analyze_sentence_similarity_with_options(&documents, &unlimited);
+    let analysis = analyze_sentence_similarity_with_options(&documents, &options);
+
+    let pairs: HashSet<_> = analysis
+        .matches
+        .iter()
+        .map(|m| (m.source_doc.clone(), m.target_doc.clone()))
+        .collect();
+    assert_eq!(analysis.matches.len(), 3);
+    assert_eq!(pairs.len(), 3);
+    assert_eq!(analysis.total_matches, 3);
+
+    // Each kept match is the best match of its pair without the limit
+    for m in &analysis.matches {
+        let best = all
+            .matches
+            .iter()
+            .find(|other| other.source_doc == m.source_doc && other.target_doc == m.target_doc)
+            .unwrap();
+        assert_eq!(page_key(m), page_key(best));
+        assert_eq!(m.similarity, best.similarity);
+    }
+}
+
+#[test]
+fn test_match_cursor_round_trip() {
+    let cursor = MatchCursor {
+        similarity: 0.95,
+        source_doc: 0,
+        source_sentence_index: 4,
+        target_doc: 1,
+        target_sentence_index: 7,
+    };
+
+    assert_eq!(MatchCursor::decode(&cursor.encode()), Some(cursor));
+    assert_eq!(MatchCursor::decode("not-a-cursor"), None);
+    assert_eq!(MatchCursor::decode("3f800000-0-1-2"), None);
+    assert_eq!(MatchCursor::decode("3f800000-0-1-2-3-4"), None);
+}
+
+#[test]
+fn test_similarity_matrices_are_symmetric() {
+    let documents = overlapping_documents();
+    let options = SentenceAnalysisOptions {
+        threshold: 0.5,
+        similarity_matrices: true,
+        aggregation: AggregationStrategy::BestMatch,
+        ..SentenceAnalysisOptions::default()
+    };
+    let analysis = analyze_sentence_similarity_with_options(&documents, &options);
+
+    let document = analysis.document_matrix.as_ref().unwrap();
+    let sentence = analysis.sentence_matrix.as_ref().unwrap();
+    assert_eq!(document.index, vec!["a.txt", "b.txt", "c.txt"]);
+    assert_eq!(sentence.index, document.index);
+
+    for matrix in [&document.matrix, &sentence.matrix] {
+        assert_eq!(matrix.len(), 3);
+        for (i, row) in matrix.iter().enumerate() {
+            assert_eq!(row[i], 1.0);
+            for (j, value) in row.iter().enumerate() {
+                assert_eq!(*value, matrix[j][i]);
+            }
+        }
+    }
+
+    // The sentence matrix holds the global scores
+    for global in &analysis.global_similarity {
+        let i = document
+            .index
+            .iter()
+            .position(|n| *n == global.doc_a)
+            .unwrap();
+        let j = document
+            .index
+            .iter()
+            .position(|n| *n == global.doc_b)
+            .unwrap();
+        assert_eq!(sentence.matrix[i][j], global.score);
+    }
+    assert!(document.matrix[0][1] > 0.0);
+
+    // Matrices are opt-in
+    let plain =
+        analyze_sentence_similarity_with_options(&documents, &SentenceAnalysisOptions::default());
+    assert!(plain.document_matrix.is_none());
+    assert!(plain.sentence_matrix.is_none());
+}
+
+#[test]
+fn test_descending_similarity_puts_nan_last() {
+    let mut scores = [0.5, f32::NAN, 1.0, 0.0, f32::NAN, 0.75];
+    scores.sort_by(|a, b| descending_similarity(*a, *b));
+
+    assert_eq!(&scores[..4], &[1.0, 0.75, 0.5, 0.0]);
+    assert!(scores[4].is_nan() && scores[5].is_nan());
+}
+
+#[test]
+fn test_global_similarity_ties_break_by_document_index() {
+    let sentences = ["Glaciers carve deep valleys over thousands of years."];
+    let documents = vec![
+        document("c.txt", &sentences),
+        document("a.txt", &sentences),
+        document("b.txt", &sentences),
+    ];
+    let analysis =
+        analyze_sentence_similarity_with_options(&documents, &SentenceAnalysisOptions::default());
+
+    // Every pair scores the same; pairs follow upload order, not names
+    let pairs: Vec<(&str, &str)> = analysis
+        .global_similarity
+        .iter()
+        .map(|g| (g.doc_a.as_str(), g.doc_b.as_str()))
+        .collect();
+    assert_eq!(
+        pairs,
+        vec![("c.txt", "a.txt"), ("c.txt", "b.txt"), ("a.txt", "b.txt")]
+    );
+}
+
+#[test]
+fn test_output_is_identical_across_runs_and_thread_counts() {
+    let documents = overlapping_documents();
+    let options = SentenceAnalysisOptions {
+        threshold: 0.1,
+        similarity_matrices: true,
+        ..SentenceAnalysisOptions::default()
+    };
+    let snapshot = |threads: usize| {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        let analysis =
+            pool.install(|| analyze_sentence_similarity_with_options(&documents, &options));
+        serde_json::to_string(&analysis.into_response(&documents, options.threshold, 0, Vec::new()))
+            .unwrap()
+    };
+
+    let expected = snapshot(1);
+    for threads in [1, 2, 4, 8] {
+        assert_eq!(snapshot(threads), expected, "{threads} threads");
+    }
+}
@@ -0,0 +1,246 @@
+//! Tests for the HTML comparison report and the CSV / JSON Lines exports
+
+use document_similarity_analyzer::core::{
+    analyze_sentence_similarity_with_options, SentenceAnalysisOptions, SentenceDocument,
+};
+use document_similarity_analyzer::models::SentenceAnalysisResponse;
+use document_similarity_analyzer::report::{
+    render_html_report, write_global_similarity_csv, write_matches_csv, write_matches_ndjson,
+    write_similarity_matrix_csv, ReportFormat,
+};
+
+fn documents() -> Vec<SentenceDocument> {
+    vec![
+        SentenceDocument::new(
+            "essay <1>.txt".to_string(),
+            vec![
+                "Glaciers carve deep valleys over thousands of years.".to_string(),
+                "Rivers meander across wide floodplains slowly.".to_string(),
+            ],
+        ),
+        SentenceDocument::new(
+            "essay 2.txt".to_string(),
+            vec![
+                "Deserts receive less than ten inches of rain yearly.".to_string(),
+                "Glaciers carve deep valleys over thousands of years.".to_string(),
+            ],
+        ),
+    ]
+}
+
+fn response(documents: &[SentenceDocument]) -> SentenceAnalysisResponse {
+    let analysis =
+        analyze_sentence_similarity_with_options(documents, &SentenceAnalysisOptions::default());
+    analysis.into_response(documents, 0.7, 1, Vec::new())
+}
+
+fn render() -> String {
+    let documents = documents();
+    render_html_report(&response(&documents), &documents)
+}
+
+fn export(
+    write: fn(&SentenceAnalysisResponse, &mut Vec<u8>) -> std::io::Result<()>,
+    documents: &[SentenceDocument],
+) -> String {
+    let mut out = Vec::new();
+    write(&response(documents), &mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn test_report_is_self_contained_html() {
+    let html = render();
+
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<style>"));
+    assert!(!html.contains("<script src"));
+    assert!(!html.contains("<link"));
+}
+
+#[test]
+fn test_report_links_matches_and_sentences() {
+    let html = render();
+
+    // Matched sentences are highlighted and link to the match row
+    assert!(html.contains(r#"<span id="d0-s0" class="sentence grade-4""#));
+    assert!(html.contains(r#"<span id="d1-s1" class="sentence grade-4""#));
+    assert!(html.contains(r##"<a href="#m1">1</a>"##));
+    // The match row links back to both sentences
+    assert!(html.contains(r#"<tr id="m1">"#));
+    assert!(html.contains(r##"href="#d0-s0""##));
+    assert!(html.contains(r##"href="#d1-s1""##));
+    // Unmatched sentences are not highlighted
+    assert!(html.contains(r#"<span id="d0-s1" class="sentence">"#));
+}
+
+#[test]
+fn test_report_keeps_documents_with_the_same_filename_apart() {
+    let mut documents = documents();
+    for doc in &mut documents {
+        doc.filename = "essay.txt".to_string();
+    }
+    let html = render_html_report(&response(&documents), &documents);
+
+    assert!(html.contains(r#"<span id="d0-s0" class="sentence grade-4""#));
+    assert!(html.contains(r#"<span id="d1-s1" class="sentence grade-4""#));
+    assert!(html.contains(r#"<span id="d1-s0" class="sentence">"#));
+    assert!(html.contains(r##"href="#d1-s1""##));
+}
+
+#[test]
+fn test_report_escapes_text() {
+    let html = render();
+
+    assert!(html.contains("essay &lt;1&gt;.txt"));
+    assert!(!html.contains("essay <1>.txt"));
+}
+
+#[test]
+fn test_report_format_names() {
+    assert_eq!(ReportFormat::from_name("HTML"), Some(ReportFormat::Html));
+    assert_eq!(ReportFormat::from_name("json"), Some(ReportFormat::Json));
+    assert_eq!(ReportFormat::from_name("pdf"), None);
+    assert_eq!(
+        ReportFormat::from_name("matrix-csv"),
+        Some(ReportFormat::MatrixCsv)
+    );
+    assert_eq!(ReportFormat::from_name("jsonl"), Some(ReportFormat::Ndjson));
+}
+
+#[test]
+fn test_report_format_from_accept() {
+    assert_eq!(
+        ReportFormat::from_accept("text/csv; charset=utf-8"),
+        Some(ReportFormat::Csv)
+    );
+    assert_eq!(
+        ReportFormat::from_accept("image/png, application/x-ndjson"),
+        Some(ReportFormat::Ndjson)
+    );
+    assert_eq!(
+        ReportFormat::from_accept("text/html,application/json"),
+        Some(ReportFormat::Html)
+    );
+    assert_eq!(ReportFormat::from_accept("*/*"), None);
+}
+
+#[test]
+fn test_report_format_from_accept_weights() {
+    assert_eq!(
+        ReportFormat::from_accept("text/html;q=0.1, application/json"),
+        Some(ReportFormat::Json)
+    );
+    assert_eq!(
+        ReportFormat::from_accept("text/csv;q=0.5, application/x-ndjson;q=0.8"),
+        Some(ReportFormat::Ndjson)
+    );
+    // A browser form post prefers HTML
+    assert_eq!(
+        ReportFormat::from_accept(
+            "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8"
+        ),
+        Some(ReportFormat::Html)
+    );
+    // q=0 means "not acceptable"
+    assert_eq!(
+        ReportFormat::from_accept("text/html;q=0, application/json;q=0.2"),
+        Some(ReportFormat::Json)
+    );
+    assert_eq!(ReportFormat::from_accept("text/html; q=0"), None);
+}
+
+#[test]
+fn test_matches_csv() {
+    let csv = export(|r, out| write_matches_csv(r, out), &documents());
+    let lines: Vec<&str> = csv.trim_start_matches('\u{feff}').split("\r\n").collect();
+
+    assert!(csv.starts_with('\u{feff}'));
+    assert!(lines[0].starts_with("source_doc,source_sentence_index,source_sentence"));
+    assert!(lines[1].starts_with("essay <1>.txt,0,Glaciers carve deep valleys"));
+    assert!(lines[1].ends_with(",false,false"));
+    assert_eq!(lines.len(), 3);
+}
+
+#[test]
+fn test_csv_quotes_and_neutralizes_formulas() {
+    let documents = vec![
+        SentenceDocument::new(
+            "=a.txt".to_string(),
+            vec!["Glaciers carve deep valleys, over \"thousands\" of years.".to_string()],
+        ),
+        SentenceDocument::new(
+            "b.txt".to_string(),
+            vec!["Glaciers carve deep valleys, over \"thousands\" of years.".to_string()],
+        ),
+    ];
+    let csv = export(|r, out| write_matches_csv(r, out), &documents);
+
+    assert!(
+        csv.contains("'=a.txt,0,\"Glaciers carve deep valleys, over \"\"thousands\"\" of years.\"")
+    );
+}
+
+#[test]
+fn test_global_similarity_csv() {
+    let csv = export(|r, out| write_global_similarity_csv(r, out), &documents());
+    let lines: Vec<&str> = csv.trim_start_matches('\u{feff}').split("\r\n").collect();
+
+    assert!(lines[0].starts_with("doc_a,doc_b,score,strategy,coverage_a_sentences"));
+    assert!(lines[1].starts_with("essay <1>.txt,essay 2.txt,"));
+    assert!(lines[1].contains(",mean,0.5,"));
+}
+
+#[test]
+fn test_similarity_matrix_csv_is_symmetric() {
+    let csv = export(|r, out| write_similarity_matrix_csv(r, out), &documents());
+    let rows: Vec<Vec<&str>> = csv
+        .trim_start_matches('\u{feff}')
+        .split("\r\n")
+        .filter(|line| !line.is_empty())
+        .map(|line| line.split(',').collect())
+        .collect();
+
+    assert_eq!(rows[0], vec!["", "essay <1>.txt", "essay 2.txt"]);
+    assert_eq!(rows[1][1], "1");
+    assert_eq!(rows[2][2], "1");
+    assert_eq!(rows[1][2], rows[2][1]);
+}
+
+#[test]
+fn test_similarity_matrix_csv_keeps_documents_with_the_same_filename_apart() {
+    let mut documents = documents();
+    documents.push(SentenceDocument::new(
+        "essay.txt".to_string(),
+        vec!["Volcanoes release gases that change the climate.".to_string()],
+    ));
+    for doc in &mut documents {
+        doc.filename = "essay.txt".to_string();
+    }
+    let csv = export(|r, out| write_similarity_matrix_csv(r, out), &documents);
+    let rows: Vec<Vec<&str>> = csv
+        .trim_start_matches('\u{feff}')
+        .split("\r\n")
+        .filter(|line| !line.is_empty())
+        .map(|line| line.split(',').collect())
+        .collect();
+
+    assert_eq!(rows.len(), 4);
+    assert_eq!(rows[1][2], rows[2][1]);
+    assert_ne!(rows[1][2], "");
+    assert_eq!(rows[1][3], rows[3][1]);
+    assert_ne!(rows[1][2], rows[1][3]);
+    for (i, row) in rows.iter().enumerate().skip(1) {
+        assert_eq!(row[i], "1");
+    }
+}
+
+#[test]
+fn test_matches_ndjson() {
+    let ndjson = export(|r, out| write_matches_ndjson(r, out), &documents());
+    let lines: Vec<&str> = ndjson.lines().collect();
+
+    assert_eq!(lines.len(), 1);
+    let value: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+    assert_eq!(value["source_doc"], "essay <1>.txt");
+}
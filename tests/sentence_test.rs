@@ -0,0 +1,297 @@
+//! Tests for sentence splitting and multilingual tokenization
+
+use document_similarity_analyzer::core::{normalize_text, tokenize, tokenize_with_mode, TokenizerMode};
+use document_similarity_analyzer::sentence::{
+    find_reference_section, has_citation, quoted_sentences, split_sentences,
+    split_sentences_with_language, Language,
+};
+
+#[test]
+fn test_splits_simple_sentences() {
+    let sentences = split_sentences("First sentence. Second one! Third one? Fourth");
+
+    assert_eq!(
+        sentences,
+        vec!["First sentence.", "Second one!", "Third one?", "Fourth"]
+    );
+}
+
+#[test]
+fn test_titles_and_initials_do_not_split() {
+    let sentences = split_sentences("Dr. Smith met John F. Kennedy in 1960. They talked.");
+
+    assert_eq!(
+        sentences,
+        vec!["Dr. Smith met John F. Kennedy in 1960.", "They talked."]
+    );
+}
+
+#[test]
+fn test_single_letters_that_are_not_initials_split() {
+    assert_eq!(
+        split_sentences("Plan B. Then we left."),
+        vec!["Plan B.", "Then we left."]
+    );
+    assert_eq!(
+        split_sentences("Take vitamin C. It helps."),
+        vec!["Take vitamin C.", "It helps."]
+    );
+    assert_eq!(
+        split_sentences("Grade a. The next one."),
+        vec!["Grade a.", "The next one."]
+    );
+}
+
+#[test]
+fn test_sequences_of_initials_do_not_split() {
+    let sentences = split_sentences("The book by J. R. R. Tolkien is long. It sold well.");
+
+    assert_eq!(
+        sentences,
+        vec!["The book by J. R. R. Tolkien is long.", "It sold well."]
+    );
+}
+
+#[test]
+fn test_abbreviations_do_not_split() {
+    let sentences = split_sentences(
+        "Some methods, e.g. this one, are fast. U.S. policy changed. Smith et al. 2020 agree.",
+    );
+
+    assert_eq!(
+        sentences,
+        vec![
+            "Some methods, e.g. this one, are fast.",
+            "U.S. policy changed.",
+            "Smith et al. 2020 agree.",
+        ]
+    );
+}
+
+#[test]
+fn test_abbreviation_at_sentence_end_splits_before_capital() {
+    let sentences = split_sentences("We bought apples, pears, etc. The shop was busy.");
+
+    assert_eq!(
+        sentences,
+        vec!["We bought apples, pears, etc.", "The shop was busy."]
+    );
+}
+
+#[test]
+fn test_decimals_do_not_split() {
+    let sentences = split_sentences("Pi is about 3.14 in value. The ratio was 3. 5 on average.");
+
+    assert_eq!(
+        sentences,
+        vec!["Pi is about 3.14 in value.", "The ratio was 3. 5 on average."]
+    );
+}
+
+#[test]
+fn test_ellipsis_splits_only_before_capital() {
+    let sentences = split_sentences("He paused... and then left... The end.");
+
+    assert_eq!(
+        sentences,
+        vec!["He paused... and then left...", "The end."]
+    );
+}
+
+#[test]
+fn test_closing_quotes_and_brackets_stay_with_sentence() {
+    let sentences = split_sentences("He said \"Stop.\" Then he left (quickly.) Nobody followed.");
+
+    assert_eq!(
+        sentences,
+        vec!["He said \"Stop.\"", "Then he left (quickly.)", "Nobody followed."]
+    );
+}
+
+#[test]
+fn test_bullet_lists_split_on_line_breaks() {
+    let text = "Key findings:\n- Solar power is growing\n- Wind farms are expanding\n1. Costs fell\n2) Storage improved";
+
+    let sentences = split_sentences(text);
+
+    assert_eq!(
+        sentences,
+        vec![
+            "Key findings:",
+            "Solar power is growing",
+            "Wind farms are expanding",
+            "Costs fell",
+            "Storage improved",
+        ]
+    );
+}
+
+#[test]
+fn test_wrapped_lines_are_joined() {
+    let sentences = split_sentences("This sentence was\nwrapped by the PDF\nextractor. Next one.");
+
+    assert_eq!(
+        sentences,
+        vec!["This sentence was wrapped by the PDF extractor.", "Next one."]
+    );
+}
+
+#[test]
+fn test_indonesian_abbreviations() {
+    let text = "Penelitian oleh Budi dkk. menunjukkan hasil baik. Bpk. Ahmad tinggal di Jl. Merdeka.";
+
+    let sentences = split_sentences_with_language(text, Language::Indonesian);
+
+    assert_eq!(
+        sentences,
+        vec![
+            "Penelitian oleh Budi dkk. menunjukkan hasil baik.",
+            "Bpk. Ahmad tinggal di Jl. Merdeka.",
+        ]
+    );
+}
+
+#[test]
+fn test_language_lists_are_separate() {
+    // "Jl." is only an abbreviation in Indonesian
+    let text = "Ahmad tinggal di Jl. Merdeka.";
+
+    assert_eq!(split_sentences_with_language(text, Language::Indonesian).len(), 1);
+    assert_eq!(split_sentences_with_language(text, Language::English).len(), 2);
+}
+
+#[test]
+fn test_language_codes() {
+    assert_eq!(Language::from_code("en"), Some(Language::English));
+    assert_eq!(Language::from_code("ID"), Some(Language::Indonesian));
+    assert_eq!(Language::from_code("auto"), Some(Language::Auto));
+    assert_eq!(Language::from_code("klingon"), None);
+}
+
+#[test]
+fn test_empty_text() {
+    assert!(split_sentences("   \n  ").is_empty());
+}
+
+#[test]
+fn test_cjk_terminators() {
+    let sentences = split_sentences("今天天气很好。我们去公园吧！你想去吗？");
+
+    assert_eq!(sentences, vec!["今天天气很好。", "我们去公园吧！", "你想去吗？"]);
+}
+
+#[test]
+fn test_arabic_and_hindi_terminators() {
+    let arabic = split_sentences_with_language("كيف حالك؟ أنا بخير.", Language::Arabic);
+    assert_eq!(arabic, vec!["كيف حالك؟", "أنا بخير."]);
+
+    let hindi = split_sentences_with_language("यह एक वाक्य है। यह दूसरा है।", Language::Hindi);
+    assert_eq!(hindi, vec!["यह एक वाक्य है।", "यह दूसरा है।"]);
+}
+
+#[test]
+fn test_multilingual_language_codes() {
+    assert_eq!(Language::from_code("zh"), Some(Language::Chinese));
+    assert_eq!(Language::from_code("ja"), Some(Language::Japanese));
+    assert_eq!(Language::from_code("ar"), Some(Language::Arabic));
+    assert_eq!(Language::from_code("hindi"), Some(Language::Hindi));
+}
+
+#[test]
+fn test_cjk_text_is_tokenized_into_bigrams() {
+    assert_eq!(tokenize("相似度"), vec!["相似", "似度"]);
+    assert_eq!(tokenize("rust 编程"), vec!["rust", "编程"]);
+    assert_eq!(tokenize("字"), vec!["字"]);
+    assert_eq!(tokenize_with_mode("相似度", TokenizerMode::Whitespace), vec!["相似度"]);
+}
+
+#[test]
+fn test_normalize_removes_unicode_punctuation() {
+    assert_eq!(normalize_text("你好，世界。"), "你好 世界");
+    assert_eq!(normalize_text("“Quoted” — text؟"), "quoted text");
+}
+
+#[test]
+fn test_quoted_sentences_across_boundaries() {
+    let sentences = split_sentences(
+        "He wrote: “Data is the new oil. It must be refined.” Then he left. \
+         She said \"this is fine\" in the end. \"Fully quoted sentence here.\"",
+    );
+
+    assert_eq!(
+        quoted_sentences(&sentences),
+        vec![true, true, false, false, true],
+        "{:?}",
+        sentences
+    );
+}
+
+#[test]
+fn test_german_quotes_close_on_upper_mark() {
+    let sentences = split_sentences(
+        "Er sagte: „Daten sind das neue Öl.“ Danach ging er nach Hause. \
+         Sie arbeitete den ganzen Tag im Büro. Am Abend las sie ein Buch.",
+    );
+
+    assert_eq!(
+        quoted_sentences(&sentences),
+        vec![true, false, false, false],
+        "{:?}",
+        sentences
+    );
+}
+
+#[test]
+fn test_unbalanced_quote_is_forgotten() {
+    let sentences: Vec<String> = std::iter::once("A stray “ mark.".to_string())
+        .chain((0..6).map(|i| format!("Ordinary sentence number {}.", i)))
+        .collect();
+
+    let quoted = quoted_sentences(&sentences);
+
+    assert!(!quoted[6]);
+}
+
+#[test]
+fn test_citation_styles() {
+    assert!(has_citation(
+        "Learning improves with practice (Smith & Lee, 2019a)."
+    ));
+    assert!(has_citation("As Smith et al. (2020) argue, data matters."));
+    assert!(has_citation("Transformers dominate NLP [12], [3, 5]."));
+    assert!(!has_citation("The value (about 2000 units) is large."));
+    assert!(!has_citation("Plain sentence without sources."));
+}
+
+fn sentences(items: &[&str]) -> Vec<String> {
+    items.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn test_reference_section_headings() {
+    let paper = sentences(&[
+        "Introduction.",
+        "References to earlier work are given below.",
+        "We propose a method.",
+        "It works well.",
+        "Daftar Pustaka",
+        "Smith, J. (2020). Deep learning.",
+    ]);
+    assert_eq!(find_reference_section(&paper), Some(4));
+
+    let joined = sentences(&[
+        "Body text one.",
+        "Body text two.",
+        "7. References [1] A. Smith, Title.",
+    ]);
+    assert_eq!(find_reference_section(&joined), Some(2));
+}
+
+#[test]
+fn test_reference_heading_in_first_half_is_ignored() {
+    let paper = sentences(&["Bibliography", "Body one.", "Body two.", "Body three."]);
+    assert_eq!(find_reference_section(&paper), None);
+
+    let prose = sentences(&["Body one.", "Body two.", "References to prior work matter."]);
+    assert_eq!(find_reference_section(&prose), None);
+}
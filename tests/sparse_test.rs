@@ -0,0 +1,189 @@
+//! Tests for interned sparse vectors and normalized dot-product similarity
+
+use document_similarity_analyzer::core::{
+    compute_cosine_similarity, compute_dot_product, compute_idf, compute_normalized_tfidf_vector,
+    compute_similarity_matrix, compute_sparse_idf, compute_tf, compute_tfidf_vector,
+    cosine_similarity, dot_product, l2_normalize, SparseVector, Vocabulary,
+};
+
+fn tokens(text: &str) -> Vec<String> {
+    text.split_whitespace().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn test_vocabulary_interns_in_first_appearance_order() {
+    let mut vocabulary = Vocabulary::new();
+
+    assert_eq!(vocabulary.intern_all(&tokens("b a b c")), vec![0, 1, 0, 2]);
+    assert_eq!(vocabulary.len(), 3);
+    assert_eq!(vocabulary.id("c"), Some(2));
+    assert_eq!(vocabulary.id("d"), None);
+    assert_eq!(vocabulary.term(1), Some("a"));
+}
+
+#[test]
+fn test_term_frequencies_are_sorted_with_norm() {
+    let tf = SparseVector::term_frequencies(&[3, 1, 3, 0]);
+
+    assert_eq!(tf.indices(), &[0, 1, 3]);
+    assert_eq!(tf.values(), &[0.25, 0.25, 0.5]);
+    assert!((tf.norm() - (0.375f32).sqrt()).abs() < 1e-6);
+    assert!(SparseVector::term_frequencies(&[]).is_empty());
+}
+
+#[test]
+fn test_from_entries_sums_repeated_ids() {
+    let vector = SparseVector::from_entries(vec![(5, 1.0), (2, 2.0), (5, 0.5)]);
+
+    assert_eq!(vector.indices(), &[2, 5]);
+    assert_eq!(vector.values(), &[2.0, 1.5]);
+}
+
+#[test]
+fn test_sparse_tfidf_matches_hashmap_pipeline() {
+    let texts = [
+        "the glacier carved the valley",
+        "a glacier carved a deep valley",
+        "rivers meander across the plain",
+    ];
+    let token_lists: Vec<Vec<String>> = texts.iter().map(|t| tokens(t)).collect();
+
+    // HashMap pipeline
+    let tfs: Vec<_> = token_lists.iter().map(|t| compute_tf(t)).collect();
+    let idf = compute_idf(&tfs);
+    let maps: Vec<_> = tfs
+        .iter()
+        .map(|tf| compute_tfidf_vector(tf, &idf))
+        .collect();
+
+    // Sparse pipeline
+    let mut vocabulary = Vocabulary::new();
+    let sparse_tfs: Vec<SparseVector> = token_lists
+        .iter()
+        .map(|t| SparseVector::term_frequencies(&vocabulary.intern_all(t)))
+        .collect();
+    let sparse_idf = compute_sparse_idf(&sparse_tfs, vocabulary.len());
+    let vectors: Vec<SparseVector> = sparse_tfs
+        .iter()
+        .map(|tf| tf.weighted(&sparse_idf))
+        .collect();
+
+    for (term, value) in &idf {
+        let id = vocabulary.id(term).unwrap() as usize;
+        assert!((sparse_idf[id] - value).abs() < 1e-6);
+    }
+    for i in 0..texts.len() {
+        for j in 0..texts.len() {
+            let expected = compute_cosine_similarity(&maps[i], &maps[j]);
+            assert!((vectors[i].cosine(&vectors[j]) - expected).abs() < 1e-5);
+        }
+    }
+}
+
+#[test]
+fn test_cosine_of_disjoint_and_empty_vectors() {
+    let a = SparseVector::term_frequencies(&[0, 1]);
+    let b = SparseVector::term_frequencies(&[2, 3]);
+
+    assert_eq!(a.dot(&b), 0.0);
+    assert_eq!(a.cosine(&b), 0.0);
+    assert_eq!(a.cosine(&SparseVector::default()), 0.0);
+    assert!((a.cosine(&a) - 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_normalized_sparse_dot_is_cosine() {
+    let a = SparseVector::from_entries(vec![(0, 3.0), (2, 4.0)]);
+    let b = SparseVector::from_entries(vec![(0, 1.0), (1, 2.0), (2, 2.0)]);
+    let (unit_a, unit_b) = (a.normalized(), b.normalized());
+
+    assert!((unit_a.norm() - 1.0).abs() < 1e-6);
+    assert_eq!(unit_a.values(), &[0.6, 0.8]);
+    assert!((unit_a.dot(&unit_b) - a.cosine(&b)).abs() < 1e-6);
+    assert!(SparseVector::default().normalized().is_empty());
+}
+
+#[test]
+fn test_dense_dot_product_matches_cosine_after_normalizing() {
+    // 19 entries: two full lanes of 8 plus a remainder of 3
+    let a: Vec<f32> = (0..19).map(|i| (i as f32 * 0.7).sin()).collect();
+    let b: Vec<f32> = (0..19).map(|i| (i as f32 * 1.3).cos()).collect();
+    let expected = cosine_similarity(&a, &b);
+
+    let (mut unit_a, mut unit_b) = (a.clone(), b.clone());
+    l2_normalize(&mut unit_a);
+    l2_normalize(&mut unit_b);
+
+    assert!((dot_product(&unit_a, &unit_b) - expected).abs() < 1e-5);
+    assert_eq!(dot_product(&a, &b[..18]), 0.0);
+
+    let mut zero = vec![0.0; 4];
+    l2_normalize(&mut zero);
+    assert_eq!(zero, vec![0.0; 4]);
+}
+
+#[test]
+fn test_similarity_matrix_matches_pairwise_cosine() {
+    let vectors = vec![
+        vec![1.0, 0.0, 2.0],
+        vec![0.5, 1.0, 0.0],
+        vec![2.0, 0.0, 4.0],
+        vec![0.0; 3],
+    ];
+    let matrix = compute_similarity_matrix(&vectors);
+
+    for (i, row) in matrix.to_rows().iter().enumerate() {
+        for (j, &value) in row.iter().enumerate() {
+            let expected = if i == j {
+                1.0
+            } else {
+                cosine_similarity(&vectors[i], &vectors[j])
+            };
+            assert!(
+                (value - expected).abs() < 1e-6,
+                "[{i}][{j}] = {value}, expected {expected}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_normalized_hashmap_dot_is_cosine() {
+    let documents = [
+        tokens("the cat sat on the mat"),
+        tokens("the dog sat on the log"),
+    ];
+    let tfs: Vec<_> = documents.iter().map(|d| compute_tf(d)).collect();
+    let idf = compute_idf(&tfs);
+
+    let expected = compute_cosine_similarity(
+        &compute_tfidf_vector(&tfs[0], &idf),
+        &compute_tfidf_vector(&tfs[1], &idf),
+    );
+    let dot = compute_dot_product(
+        &compute_normalized_tfidf_vector(&tfs[0], &idf),
+        &compute_normalized_tfidf_vector(&tfs[1], &idf),
+    );
+
+    assert!((dot - expected).abs() < 1e-6);
+}
+
+#[test]
+fn test_hashmap_similarity_is_bit_identical_across_maps() {
+    let a: Vec<String> = (0..200).map(|i| format!("term{}", i % 90)).collect();
+    let b: Vec<String> = (0..200).map(|i| format!("term{}", (i * 7) % 120)).collect();
+    let expected = compute_cosine_similarity(&compute_tf(&a), &compute_tf(&b));
+
+    // Every map gets its own hash seed, so iteration order differs between builds
+    for _ in 0..20 {
+        let (tf_a, tf_b) = (compute_tf(&a), compute_tf(&b));
+        assert_eq!(
+            compute_cosine_similarity(&tf_a, &tf_b).to_bits(),
+            expected.to_bits()
+        );
+        assert_eq!(
+            compute_cosine_similarity(&tf_b, &tf_a).to_bits(),
+            expected.to_bits()
+        );
+    }
+}